@@ -0,0 +1,826 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+#[macro_use]
+extern crate common;
+
+pub mod types;
+
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+use common::errors::{report_error, FluetError, ReportKind, Result};
+use common::expr::{Arg, Expr};
+use common::location::Location;
+use common::pattern::Pattern;
+use common::stmt::{Function, Stmt};
+use common::token::{Literal, Token, TokenType};
+use common::types::TypeExpr;
+
+use types::Type;
+
+/// Checks that values match the types they are annotated with, or the types
+/// inferred from how they are made, before the program runs. Anything that
+/// can't be inferred is left to runtime checks.
+///
+/// Values that don't match annotations are errors. Operators that can't take
+/// the types inferred for their operands are only warnings, since the code
+/// may be counting on the error they throw.
+pub struct Checker {
+    scopes: Vec<HashMap<String, Variable>>,
+    classes: HashSet<String>,
+    /// The annotated return type of the function being checked, if any.
+    returns: Option<Type>,
+    /// The types of the values returned so far by the function being checked.
+    returned: Vec<Type>,
+    hovers: Vec<Hover>,
+    warnings: Vec<FluetError>,
+    /// The names assigned to anywhere in the checked code.
+    assigned: HashSet<String>,
+    /// The first scope of the function being checked. Variables from the
+    /// scopes before it are captured, and could have been assigned anything by
+    /// the time the function runs.
+    function_scope: usize,
+}
+
+struct Variable {
+    r#type: Type,
+    /// Whether the type was annotated rather than inferred. Assigning a value
+    /// of another type to an inferred variable makes it `any`.
+    annotated: bool,
+}
+
+/// The type of a name where it appears in the source.
+#[derive(Debug, Clone)]
+pub struct Hover {
+    pub name: Token,
+    pub r#type: Type,
+}
+
+impl Checker {
+    pub fn new() -> Self {
+        Self {
+            scopes: vec![HashMap::new()],
+            classes: HashSet::new(),
+            returns: None,
+            returned: vec![],
+            hovers: vec![],
+            warnings: vec![],
+            assigned: HashSet::new(),
+            function_scope: 0,
+        }
+    }
+
+    /// Takes the warnings found while checking.
+    pub fn take_warnings(&mut self) -> Vec<FluetError> {
+        mem::take(&mut self.warnings)
+    }
+
+    /// The type of the name at a 1-based row and column, if one was checked.
+    pub fn type_at(&self, row: usize, column: usize) -> Option<&Hover> {
+        // Token locations point at their last character
+        self.hovers.iter().rev().find(|hover| {
+            let location = hover.name.location();
+            let start = (location.column + 1).saturating_sub(hover.name.lexeme().chars().count());
+            location.row == row && (start..=location.column).contains(&column)
+        })
+    }
+
+    /// The types of every name that was checked, in the order they appear.
+    pub fn hovers(&self) -> &[Hover] {
+        &self.hovers
+    }
+
+    pub fn check(&mut self, stmts: &[Stmt]) -> Result<()> {
+        // Classes can be used in annotations before they are declared
+        collect_classes(stmts, &mut self.classes);
+        collect_assigned(stmts, &mut self.assigned);
+
+        for stmt in stmts {
+            self.check_stmt(stmt)?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks an expression after the statements before it, and gives its type.
+    pub fn check_expr(&mut self, expr: &Expr) -> Result<Type> {
+        self.type_of(expr)
+    }
+
+    fn check_stmt(&mut self, stmt: &Stmt) -> Result<()> {
+        match stmt {
+            Stmt::Class(_, name, interfaces, methods, ..) => {
+                for interface in interfaces {
+                    self.type_of(interface)?;
+                }
+
+                self.classes.insert(name.lexeme().to_string());
+                self.declare(name, Type::Class(name.lexeme().to_string()), true);
+
+                self.begin_scope();
+                self.define("this", Type::Instance(name.lexeme().to_string()), true);
+                for method in methods {
+                    self.check_function(method)?;
+                }
+                self.end_scope();
+                Ok(())
+            },
+            Stmt::Expr(expr) => self.type_of(expr).map(|_| ()),
+            Stmt::Fn(function) => {
+                // Recursive calls see the annotated type, before the return
+                // type is inferred
+                let params = self.param_types(function)?;
+                let r#type = Type::Fn(params.clone(), Box::new(self.annotated_return(function)?));
+                self.define(function.name.lexeme(), r#type, false);
+
+                let ret = self.check_function(function)?;
+                self.declare(&function.name, Type::Fn(params, Box::new(ret)), false);
+                Ok(())
+            },
+            Stmt::For(pattern, iterable, body) => {
+                let element = match self.type_of(iterable)? {
+                    Type::List(element) => *element,
+                    _ => Type::Any,
+                };
+
+                self.begin_scope();
+                self.declare_pattern(pattern, element, false);
+                self.check_stmts(body)?;
+                self.end_scope();
+                Ok(())
+            },
+            Stmt::Interface(name, _, _) => {
+                self.declare(name, Type::Interface(name.lexeme().to_string()), true);
+                Ok(())
+            },
+            Stmt::Let(pattern, annotation, expr, _) => {
+                let actual = self.type_of(expr)?;
+                match annotation {
+                    Some(annotation) => {
+                        let expected = self.resolve_type(annotation)?;
+                        self.expect(&expected, &actual, expr, pattern.token().location())?;
+                        self.declare_pattern(pattern, expected, true);
+                    },
+                    // `let x;` leaves a variable to be assigned later
+                    None if actual == Type::Null => self.declare_pattern(pattern, Type::Any, false),
+                    None => self.declare_pattern(pattern, actual, false),
+                }
+
+                Ok(())
+            },
+            Stmt::Loop(body) => self.check_scoped(body),
+            Stmt::Return(keyword, expr) => {
+                let actual = self.type_of(expr)?;
+                self.returned.push(actual.clone());
+                match self.returns.clone() {
+                    Some(expected) if !expected.accepts(&actual) => error!(
+                        ReportKind::TypeError,
+                        &format!("Expected to return {expected} but found {actual}."),
+                        expr_location(expr).unwrap_or(keyword.location())
+                    ),
+                    _ => Ok(()),
+                }
+            },
+            Stmt::Throw(_, expr) => self.type_of(expr).map(|_| ()),
+            Stmt::Try(body, catch, finally) => {
+                self.check_scoped(body)?;
+
+                if let Some((name, handler)) = catch {
+                    self.begin_scope();
+                    self.declare(name, Type::Any, false);
+                    self.check_stmts(handler)?;
+                    self.end_scope();
+                }
+
+                if let Some(finally) = finally {
+                    self.check_scoped(finally)?;
+                }
+
+                Ok(())
+            },
+            Stmt::While(_, condition, body) => {
+                self.type_of(condition)?;
+                self.check_scoped(body)
+            },
+            Stmt::Yield(_, expr) => self.type_of(expr).map(|_| ()),
+        }
+    }
+
+    fn check_stmts(&mut self, stmts: &[Stmt]) -> Result<()> {
+        for stmt in stmts {
+            self.check_stmt(stmt)?;
+        }
+
+        Ok(())
+    }
+
+    fn check_scoped(&mut self, stmts: &[Stmt]) -> Result<()> {
+        self.begin_scope();
+        let result = self.check_stmts(stmts);
+        self.end_scope();
+        result
+    }
+
+    /// Checks a function and gives its return type, inferred from what it
+    /// returns unless it's annotated.
+    fn check_function(&mut self, function: &Function) -> Result<Type> {
+        let enclosing_scope = mem::replace(&mut self.function_scope, self.scopes.len());
+        self.begin_scope();
+        let result = self.check_params(function).and_then(|_| {
+            // Whatever a generator's body returns, calling it gives a generator
+            let returns = match &function.returns {
+                Some(_) if function.generator => None,
+                Some(annotation) => Some(self.resolve_type(annotation)?),
+                None => None,
+            };
+
+            let enclosing = mem::replace(&mut self.returns, returns);
+            let enclosing_returned = mem::take(&mut self.returned);
+            let trailing = self.check_body(function);
+            self.returns = enclosing;
+            let returned = mem::replace(&mut self.returned, enclosing_returned);
+
+            let inferred = returned.into_iter().chain(trailing?).reduce(|lhs, rhs| lhs.join(&rhs));
+            match self.annotated_return(function)? {
+                Type::Any => Ok(inferred.unwrap_or(Type::Any)),
+                annotated => Ok(annotated),
+            }
+        });
+        self.end_scope();
+        self.function_scope = enclosing_scope;
+        result
+    }
+
+    fn check_params(&mut self, function: &Function) -> Result<()> {
+        for param in &function.params {
+            let r#type = match &param.annotation {
+                Some(annotation) => self.resolve_type(annotation)?,
+                None if param.rest => Type::List(Box::new(Type::Any)),
+                None => Type::Any,
+            };
+
+            if let Some(default) = &param.default {
+                let actual = self.type_of(default)?;
+                self.expect(&r#type, &actual, default, param.pattern.token().location())?;
+            }
+
+            self.declare_pattern(&param.pattern, r#type, param.annotation.is_some());
+        }
+
+        Ok(())
+    }
+
+    /// Checks the body of a function, and gives the type of its trailing
+    /// expression unless the body always returns before it.
+    fn check_body(&mut self, function: &Function) -> Result<Option<Type>> {
+        self.check_stmts(&function.body)?;
+        let actual = self.type_of(&function.ret)?;
+
+        // The trailing expression is never reached when every path returns
+        if always_returns(&function.body) {
+            return Ok(None);
+        }
+
+        if let Some(expected) = self.returns.clone() {
+            let location = expr_location(&function.ret).unwrap_or(function.name.location());
+            self.expect(&expected, &actual, &function.ret, location)?;
+        }
+
+        Ok(Some(actual))
+    }
+
+    fn param_types(&self, function: &Function) -> Result<Vec<Type>> {
+        let mut params = vec![];
+        for param in function.params.iter().filter(|param| !param.rest) {
+            params.push(match &param.annotation {
+                Some(annotation) => self.resolve_type(annotation)?,
+                None => Type::Any,
+            });
+        }
+
+        Ok(params)
+    }
+
+    /// What calling a function gives according to its annotations, which is
+    /// `any` if it isn't annotated.
+    fn annotated_return(&self, function: &Function) -> Result<Type> {
+        match &function.returns {
+            _ if function.generator => Ok(Type::Generator),
+            Some(annotation) => self.resolve_type(annotation),
+            None => Ok(Type::Any),
+        }
+    }
+
+    fn type_of(&mut self, expr: &Expr) -> Result<Type> {
+        match expr {
+            Expr::Assignment(_, name, value) => {
+                let actual = self.type_of(value)?;
+                let variable = self.scopes
+                    .iter_mut()
+                    .rev()
+                    .find_map(|scope| scope.get_mut(name.lexeme()));
+
+                match variable {
+                    Some(variable) if !variable.r#type.accepts(&actual) => {
+                        if variable.annotated {
+                            return error!(
+                                ReportKind::TypeError,
+                                &format!(
+                                    "Cannot assign {actual} to '{}', which is {}.",
+                                    name.lexeme(),
+                                    variable.r#type
+                                ),
+                                name.location()
+                            );
+                        }
+
+                        variable.r#type = Type::Any;
+                    },
+                    _ => {},
+                }
+
+                self.record(name, actual.clone());
+                Ok(actual)
+            },
+            Expr::Binary(lhs, op, rhs) => {
+                let lhs = self.type_of(lhs)?;
+                let rhs = self.type_of(rhs)?;
+                Ok(self.binary_type(&lhs, op, &rhs))
+            },
+            Expr::Block(_, stmts, expr, _) => {
+                self.begin_scope();
+                let result = self.check_stmts(stmts).and_then(|_| self.type_of(expr));
+                self.end_scope();
+                result
+            },
+            Expr::Call(callee, paren, args) => {
+                let callee = self.type_of(callee)?;
+                self.call_type(&callee, paren, args)
+            },
+            Expr::Get(object, _, _) => {
+                self.type_of(object)?;
+                Ok(Type::Any)
+            },
+            Expr::Grouping(expr) => self.type_of(expr),
+            Expr::If(_, condition, then_branch, else_branch) => {
+                self.type_of(condition)?;
+                let then_type = self.type_of(then_branch)?;
+                let else_type = self.type_of(else_branch)?;
+                Ok(then_type.join(&else_type))
+            },
+            Expr::Index(object, _, index) => {
+                let object = self.type_of(object)?;
+                self.type_of(index)?;
+                match object {
+                    Type::List(element) => Ok(*element),
+                    _ => Ok(Type::Any),
+                }
+            },
+            Expr::List(elements) => {
+                let mut element = None;
+                for expr in elements {
+                    let r#type = match expr {
+                        Expr::Spread(_, _) => {
+                            self.type_of(expr)?;
+                            Type::Any
+                        },
+                        expr => self.type_of(expr)?,
+                    };
+
+                    element = match element {
+                        None => Some(r#type),
+                        Some(element) if element == r#type => Some(element),
+                        Some(_) => Some(Type::Any),
+                    };
+                }
+
+                Ok(Type::List(Box::new(element.unwrap_or(Type::Any))))
+            },
+            Expr::Literal(literal) => Ok(match literal {
+                Literal::Bool(_) => Type::Bool,
+                Literal::Null => Type::Null,
+                Literal::Number(_) => Type::Number,
+                Literal::String(_) => Type::String,
+            }),
+            Expr::Logical(lhs, _, rhs) => {
+                let lhs = self.type_of(lhs)?;
+                let rhs = self.type_of(rhs)?;
+                Ok(lhs.join(&rhs))
+            },
+            Expr::Set(object, _, value) => {
+                self.type_of(object)?;
+                self.type_of(value)
+            },
+            Expr::Spawn(_, function) => {
+                self.check_function(function)?;
+                Ok(Type::Any)
+            },
+            Expr::Spread(_, expr) => {
+                self.type_of(expr)?;
+                Ok(Type::Any)
+            },
+            Expr::This(_, keyword) => {
+                let r#type = self.lookup(keyword.lexeme());
+                self.record(keyword, r#type.clone());
+                Ok(r#type)
+            },
+            Expr::Tuple(elements) => {
+                let mut types = vec![];
+                for element in elements {
+                    types.push(self.type_of(element)?);
+                }
+
+                Ok(Type::Tuple(types))
+            },
+            Expr::Unary(op, expr) => {
+                let r#type = self.type_of(expr)?;
+                match op.token_type() {
+                    TokenType::Minus if r#type.is_dynamic() => Ok(Type::Any),
+                    TokenType::Minus if r#type == Type::Number => Ok(Type::Number),
+                    TokenType::Minus => {
+                        self.warn(
+                            &format!("Unary minus operator can only be applied to numbers, not {type}."),
+                            op.location()
+                        );
+                        Ok(Type::Any)
+                    },
+                    _ => Ok(Type::Bool),
+                }
+            },
+            Expr::Variable(_, name) => {
+                let r#type = self.lookup(name.lexeme());
+                self.record(name, r#type.clone());
+                Ok(r#type)
+            },
+        }
+    }
+
+    fn call_type(&mut self, callee: &Type, paren: &Token, args: &[Arg]) -> Result<Type> {
+        let params = match callee {
+            Type::Fn(params, _) => params.as_slice(),
+            _ => &[],
+        };
+
+        // Arguments after a spread can't be matched up with parameters
+        let mut positional = Some(0);
+        for arg in args {
+            let actual = self.type_of(arg.expr())?;
+            match (arg, positional) {
+                (Arg::Positional(Expr::Spread(_, _)), _) => positional = None,
+                (Arg::Positional(expr), Some(i)) => {
+                    if let Some(expected) = params.get(i) {
+                        let location = expr_location(expr).unwrap_or(paren.location());
+                        self.expect(expected, &actual, expr, location)?;
+                    }
+
+                    positional = Some(i + 1);
+                },
+                _ => {},
+            }
+        }
+
+        match callee {
+            Type::Class(name) => Ok(Type::Instance(name.clone())),
+            Type::Fn(_, ret) => Ok(*ret.clone()),
+            Type::Bool | Type::Channel | Type::Error | Type::Generator | Type::Interface(_) | Type::List(_)
+            | Type::Null | Type::Number | Type::String | Type::Tuple(_) => {
+                self.warn(&format!("{callee} is not a function"), paren.location());
+                Ok(Type::Any)
+            },
+            _ => Ok(Type::Any),
+        }
+    }
+
+    fn expect(&self, expected: &Type, actual: &Type, expr: &Expr, location: &Location) -> Result<()> {
+        if expected.accepts(actual) {
+            return Ok(());
+        }
+
+        error!(
+            ReportKind::TypeError,
+            &format!("Expected {expected} but found {actual}."),
+            expr_location(expr).unwrap_or(location)
+        )
+    }
+
+    /// The type of a binary operation, or `any` with a warning if the
+    /// operator can't take its operands.
+    fn binary_type(&mut self, lhs: &Type, op: &Token, rhs: &Type) -> Type {
+        match op.token_type() {
+            TokenType::EqualEqual | TokenType::BangEqual => return Type::Bool,
+            _ if lhs.is_dynamic() || rhs.is_dynamic() => return Type::Any,
+            _ => {},
+        }
+
+        match (lhs, op.token_type(), rhs) {
+            (
+                Type::Number,
+                TokenType::Greater | TokenType::GreaterEqual | TokenType::Less | TokenType::LessEqual,
+                Type::Number,
+            ) => Type::Bool,
+            (
+                Type::Number,
+                TokenType::Minus | TokenType::Percent | TokenType::Plus | TokenType::Slash | TokenType::Star,
+                Type::Number,
+            ) => Type::Number,
+            (Type::String, TokenType::Plus, _) | (_, TokenType::Plus, Type::String) => Type::String,
+            (lhs, token_type, rhs) => {
+                self.warn(&format!("Cannot apply '{token_type}' to {lhs} and {rhs}."), op.location());
+                Type::Any
+            },
+        }
+    }
+
+    fn warn(&mut self, message: &str, location: &Location) {
+        self.warnings.push(report_error(ReportKind::Warning, None, message, location));
+    }
+
+    fn resolve_type(&self, annotation: &TypeExpr) -> Result<Type> {
+        match annotation {
+            TypeExpr::Fn(_, params, ret) => {
+                let mut param_types = vec![];
+                for param in params {
+                    param_types.push(self.resolve_type(param)?);
+                }
+
+                Ok(Type::Fn(param_types, Box::new(self.resolve_type(ret)?)))
+            },
+            TypeExpr::List(_, element) => Ok(Type::List(Box::new(self.resolve_type(element)?))),
+            TypeExpr::Name(name) => match name.lexeme() {
+                "any" => Ok(Type::Any),
+                "bool" => Ok(Type::Bool),
+                "chan" => Ok(Type::Channel),
+                "error" => Ok(Type::Error),
+                "generator" => Ok(Type::Generator),
+                "null" => Ok(Type::Null),
+                "number" => Ok(Type::Number),
+                "string" => Ok(Type::String),
+                class if self.classes.contains(class) => Ok(Type::Instance(class.to_string())),
+                unknown => error!(
+                    ReportKind::TypeError,
+                    &format!("Unknown type '{unknown}'."),
+                    name.location()
+                ),
+            },
+            TypeExpr::Tuple(_, elements) => {
+                let mut types = vec![];
+                for element in elements {
+                    types.push(self.resolve_type(element)?);
+                }
+
+                Ok(Type::Tuple(types))
+            },
+        }
+    }
+
+    /// Gives the names bound by a pattern their types. Destructured names get
+    /// the element types of lists and tuples, but fields of objects aren't
+    /// known.
+    fn declare_pattern(&mut self, pattern: &Pattern, r#type: Type, annotated: bool) {
+        match pattern {
+            Pattern::List(_, elements, rest) => {
+                for (i, (pattern, _)) in elements.iter().enumerate() {
+                    let element = match &r#type {
+                        Type::List(element) => *element.clone(),
+                        Type::Tuple(elements) => elements.get(i).cloned().unwrap_or(Type::Any),
+                        _ => Type::Any,
+                    };
+
+                    self.declare_pattern(pattern, element, false);
+                }
+
+                if let Some(rest) = rest {
+                    let rest_type = match &r#type {
+                        Type::List(_) => r#type.clone(),
+                        _ => Type::List(Box::new(Type::Any)),
+                    };
+
+                    self.declare(rest, rest_type, false);
+                }
+            },
+            Pattern::Name(name) => self.declare(name, r#type, annotated),
+            Pattern::Object(_, fields) => {
+                for (_, pattern, _) in fields {
+                    self.declare_pattern(pattern, Type::Any, false);
+                }
+            },
+        }
+    }
+
+    fn declare(&mut self, name: &Token, r#type: Type, annotated: bool) {
+        self.record(name, r#type.clone());
+        self.define(name.lexeme(), r#type, annotated);
+    }
+
+    fn define(&mut self, name: &str, r#type: Type, annotated: bool) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), Variable { r#type, annotated });
+        }
+    }
+
+    fn record(&mut self, name: &Token, r#type: Type) {
+        self.hovers.push(Hover { name: name.clone(), r#type });
+    }
+
+    /// The type of a variable. Names that aren't declared in the checked code,
+    /// like native functions, can be anything, and so can captured variables
+    /// that are assigned somewhere without an annotation.
+    fn lookup(&self, name: &str) -> Type {
+        let found = self.scopes
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(depth, scope)| scope.get(name).map(|variable| (depth, variable)));
+
+        let Some((depth, variable)) = found else {
+            return Type::Any;
+        };
+
+        let captured = depth < self.function_scope;
+        if captured && !variable.annotated && self.assigned.contains(name) {
+            return Type::Any;
+        }
+
+        variable.r#type.clone()
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+}
+
+impl Default for Checker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The location of a token in `expr` to point errors at.
+fn expr_location(expr: &Expr) -> Option<&Location> {
+    match expr {
+        Expr::Assignment(_, token, _)
+        | Expr::Binary(_, token, _)
+        | Expr::Call(_, token, _)
+        | Expr::Get(_, _, token)
+        | Expr::Index(_, token, _)
+        | Expr::Logical(_, token, _)
+        | Expr::Set(_, token, _)
+        | Expr::Spawn(token, _)
+        | Expr::Spread(token, _)
+        | Expr::This(_, token)
+        | Expr::Unary(token, _)
+        | Expr::Variable(_, token) => Some(token.location()),
+        Expr::Block(_, _, expr, _) | Expr::Grouping(expr) => expr_location(expr),
+        Expr::If(keyword, _, _, _) => Some(keyword.location()),
+        Expr::List(elements) | Expr::Tuple(elements) => elements.iter().find_map(expr_location),
+        Expr::Literal(_) => None,
+    }
+}
+
+fn collect_classes(stmts: &[Stmt], classes: &mut HashSet<String>) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Class(_, name, _, methods, ..) => {
+                classes.insert(name.lexeme().to_string());
+                for method in methods {
+                    collect_classes(&method.body, classes);
+                }
+            },
+            Stmt::Fn(function) => collect_classes(&function.body, classes),
+            Stmt::For(_, _, body) | Stmt::Loop(body) | Stmt::While(_, _, body) => {
+                collect_classes(body, classes)
+            },
+            Stmt::Try(body, catch, finally) => {
+                collect_classes(body, classes);
+                if let Some((_, handler)) = catch {
+                    collect_classes(handler, classes);
+                }
+                if let Some(finally) = finally {
+                    collect_classes(finally, classes);
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Collects the names of every variable assigned to in the statements.
+fn collect_assigned(stmts: &[Stmt], assigned: &mut HashSet<String>) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::Class(_, _, _, methods, ..) => {
+                for method in methods {
+                    collect_assigned_in_function(method, assigned);
+                }
+            },
+            Stmt::Expr(expr)
+            | Stmt::Let(_, _, expr, _)
+            | Stmt::Return(_, expr)
+            | Stmt::Throw(_, expr)
+            | Stmt::Yield(_, expr) => collect_assigned_in_expr(expr, assigned),
+            Stmt::Fn(function) => collect_assigned_in_function(function, assigned),
+            Stmt::For(_, expr, body) | Stmt::While(_, expr, body) => {
+                collect_assigned_in_expr(expr, assigned);
+                collect_assigned(body, assigned);
+            },
+            Stmt::Interface(..) => {},
+            Stmt::Loop(body) => collect_assigned(body, assigned),
+            Stmt::Try(body, catch, finally) => {
+                collect_assigned(body, assigned);
+                if let Some((_, handler)) = catch {
+                    collect_assigned(handler, assigned);
+                }
+                if let Some(finally) = finally {
+                    collect_assigned(finally, assigned);
+                }
+            },
+        }
+    }
+}
+
+fn collect_assigned_in_function(function: &Function, assigned: &mut HashSet<String>) {
+    for default in function.params.iter().filter_map(|param| param.default.as_ref()) {
+        collect_assigned_in_expr(default, assigned);
+    }
+
+    collect_assigned(&function.body, assigned);
+    collect_assigned_in_expr(&function.ret, assigned);
+}
+
+fn collect_assigned_in_expr(expr: &Expr, assigned: &mut HashSet<String>) {
+    match expr {
+        Expr::Assignment(_, name, value) => {
+            assigned.insert(name.lexeme().to_string());
+            collect_assigned_in_expr(value, assigned);
+        },
+        Expr::Binary(lhs, _, rhs)
+        | Expr::Index(lhs, _, rhs)
+        | Expr::Logical(lhs, _, rhs)
+        | Expr::Set(lhs, _, rhs) => {
+            collect_assigned_in_expr(lhs, assigned);
+            collect_assigned_in_expr(rhs, assigned);
+        },
+        Expr::Block(_, stmts, expr, _) => {
+            collect_assigned(stmts, assigned);
+            collect_assigned_in_expr(expr, assigned);
+        },
+        Expr::Call(callee, _, args) => {
+            collect_assigned_in_expr(callee, assigned);
+            for arg in args {
+                collect_assigned_in_expr(arg.expr(), assigned);
+            }
+        },
+        Expr::Get(expr, _, _) | Expr::Grouping(expr) | Expr::Spread(_, expr) | Expr::Unary(_, expr) => {
+            collect_assigned_in_expr(expr, assigned)
+        },
+        Expr::If(_, condition, then_branch, else_branch) => {
+            collect_assigned_in_expr(condition, assigned);
+            collect_assigned_in_expr(then_branch, assigned);
+            collect_assigned_in_expr(else_branch, assigned);
+        },
+        Expr::List(elements) | Expr::Tuple(elements) => {
+            for element in elements {
+                collect_assigned_in_expr(element, assigned);
+            }
+        },
+        Expr::Spawn(_, function) => collect_assigned_in_function(function, assigned),
+        Expr::Literal(_) | Expr::This(..) | Expr::Variable(..) => {},
+    }
+}
+
+/// Whether every path through the statements returns or throws, so the code
+/// after them never runs.
+fn always_returns(stmts: &[Stmt]) -> bool {
+    stmts.iter().any(|stmt| match stmt {
+        Stmt::Return(_, _) | Stmt::Throw(_, _) => true,
+        // There is no `break`, so a loop only ends by returning or throwing
+        Stmt::Loop(_) => true,
+        Stmt::Expr(expr) => expr_always_returns(expr),
+        Stmt::Try(body, catch, finally) => {
+            finally.as_deref().is_some_and(always_returns)
+                || always_returns(body) && catch.as_ref().is_none_or(|(_, handler)| always_returns(handler))
+        },
+        _ => false,
+    })
+}
+
+fn expr_always_returns(expr: &Expr) -> bool {
+    match expr {
+        Expr::Block(_, stmts, expr, _) => always_returns(stmts) || expr_always_returns(expr),
+        Expr::Grouping(expr) => expr_always_returns(expr),
+        Expr::If(_, _, then_branch, else_branch) => {
+            expr_always_returns(then_branch) && expr_always_returns(else_branch)
+        },
+        _ => false,
+    }
+}
@@ -0,0 +1,107 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Type {
+    /// The type of anything that isn't annotated, which is compatible with
+    /// every other type.
+    Any,
+    Bool,
+    Channel,
+    /// A class itself, which makes instances of it when called.
+    Class(String),
+    Error,
+    Fn(Vec<Type>, Box<Type>),
+    Generator,
+    Instance(String),
+    Interface(String),
+    List(Box<Type>),
+    Null,
+    Number,
+    String,
+    Tuple(Vec<Type>),
+}
+
+impl Type {
+    /// Whether a value of type `other` can be used where `self` is expected.
+    pub fn accepts(&self, other: &Type) -> bool {
+        match (self, other) {
+            (Type::Any, _) | (_, Type::Any) => true,
+            (Type::List(lhs), Type::List(rhs)) => lhs.accepts(rhs),
+            (Type::Tuple(lhs), Type::Tuple(rhs)) => {
+                lhs.len() == rhs.len() && lhs.iter().zip(rhs).all(|(lhs, rhs)| lhs.accepts(rhs))
+            },
+            (Type::Fn(lhs_params, lhs_ret), Type::Fn(rhs_params, rhs_ret)) => {
+                lhs_params.len() == rhs_params.len()
+                    && lhs_params.iter().zip(rhs_params).all(|(lhs, rhs)| rhs.accepts(lhs))
+                    && lhs_ret.accepts(rhs_ret)
+            },
+            (lhs, rhs) => lhs == rhs,
+        }
+    }
+
+    /// A type that values of both types have, which is `any` if they don't
+    /// have much in common.
+    pub fn join(&self, other: &Type) -> Type {
+        match (self, other) {
+            (Type::List(lhs), Type::List(rhs)) => Type::List(Box::new(lhs.join(rhs))),
+            (lhs, rhs) if lhs == rhs => lhs.clone(),
+            _ => Type::Any,
+        }
+    }
+
+    /// Whether operators on values of this type may be overloaded or
+    /// otherwise can't be checked.
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self, Type::Any | Type::Instance(_))
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Any => write!(f, "any"),
+            Type::Bool => write!(f, "bool"),
+            Type::Channel => write!(f, "chan"),
+            Type::Class(name) => write!(f, "class {}", name),
+            Type::Error => write!(f, "error"),
+            Type::Fn(params, ret) => {
+                write!(f, "fn(")?;
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", param)?;
+                }
+                write!(f, ") -> {}", ret)
+            },
+            Type::Generator => write!(f, "generator"),
+            Type::Instance(name) => write!(f, "{}", name),
+            Type::Interface(name) => write!(f, "interface {}", name),
+            Type::List(element) => write!(f, "[{}]", element),
+            Type::Null => write!(f, "null"),
+            Type::Number => write!(f, "number"),
+            Type::String => write!(f, "string"),
+            Type::Tuple(elements) => {
+                write!(f, "(")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                if elements.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            },
+        }
+    }
+}
@@ -0,0 +1,40 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use checker::Checker;
+use lexer::Lexer;
+use parser::Parser;
+
+fn check(source: &str) -> Checker {
+    let tokens = Lexer::new(source.to_string(), "<test>".to_string()).scan_tokens().to_vec();
+    let statements = Parser::new(tokens).parse().unwrap();
+
+    let mut checker = Checker::new();
+    checker.check(&statements).unwrap();
+    checker
+}
+
+fn type_at(checker: &Checker, row: usize, column: usize) -> String {
+    checker.type_at(row, column).unwrap().r#type.to_string()
+}
+
+#[test]
+fn inferred_types_are_queryable() {
+    let checker = check(r#"let count = 1;
+let names = ['a', 'b'];
+fn greet(name: string) { 'hello ' + name }
+let pick = if count > 0 then names[0] else 'nobody';
+let greeting = greet(pick);"#);
+
+    assert_eq!(type_at(&checker, 1, 5), "number");
+    assert_eq!(type_at(&checker, 2, 9), "[string]");
+    assert_eq!(type_at(&checker, 3, 4), "fn(string) -> string");
+    assert_eq!(type_at(&checker, 4, 5), "string");
+    assert_eq!(type_at(&checker, 5, 19), "fn(string) -> string");
+    assert!(checker.type_at(5, 1).is_none());
+}
@@ -18,32 +18,71 @@ use crate::location::Location;
 pub type Result<T> = std::result::Result<T, FluetError>;
 
 #[derive(Debug)]
-pub struct FluetError(pub String);
+pub struct FluetError {
+    pub kind: ReportKind,
+    pub message: String,
+    pub location: Location,
+    report: String,
+}
+
+impl FluetError {
+    /// Combines several errors into one that reports all of them, and is
+    /// otherwise the first one.
+    pub fn combine(errors: Vec<FluetError>) -> FluetError {
+        let mut errors = errors.into_iter();
+        let mut first = errors.next().expect("no errors to combine");
+        for error in errors {
+            first.report.push_str("\n\n");
+            first.report.push_str(&error.report);
+        }
+
+        first
+    }
+}
 
 impl Error for FluetError {
     fn description(&self) -> &str {
-        &self.0
+        &self.report
     }
 }
 
 impl fmt::Display for FluetError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.report)
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReportKind {
+    /// A failed `assert`, `assert_eq` or `assert_ne`.
+    AssertionError,
+    /// A value thrown by a `throw` statement that wasn't caught.
+    Exception,
     RuntimeError,
     SyntaxError,
     TypeError,
+    /// Something suspicious that doesn't stop the program from running.
+    Warning,
+}
+
+impl ReportKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReportKind::AssertionError => "AssertionError",
+            ReportKind::Exception => "Exception",
+            ReportKind::RuntimeError => "RuntimeError",
+            ReportKind::SyntaxError => "SyntaxError",
+            ReportKind::TypeError => "TypeError",
+            ReportKind::Warning => "Warning",
+        }
+    }
 }
 
 impl fmt::Display for ReportKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            ReportKind::RuntimeError => write!(f, "{}", "RuntimeError".red().bold()),
-            ReportKind::SyntaxError => write!(f, "{}", "SyntaxError".red().bold()),
-            ReportKind::TypeError => write!(f, "{}", "TypeError".red().bold()),
+            ReportKind::Warning => write!(f, "{}", self.name().yellow().bold()),
+            _ => write!(f, "{}", self.name().red().bold()),
         }
     }
 }
@@ -76,9 +115,12 @@ pub fn report_error(
     location: &Location,
 ) -> FluetError {
     let annotation_type = match report_kind {
-        ReportKind::RuntimeError | ReportKind::SyntaxError | ReportKind::TypeError => {
-            AnnotationType::Error
-        }
+        ReportKind::AssertionError
+        | ReportKind::Exception
+        | ReportKind::RuntimeError
+        | ReportKind::SyntaxError
+        | ReportKind::TypeError => AnnotationType::Error,
+        ReportKind::Warning => AnnotationType::Warning,
     };
 
     report(annotation_type, report_kind, id, message, location)
@@ -91,7 +133,11 @@ pub fn report(
     message: &str,
     location: &Location,
 ) -> FluetError {
-    let title = format!("{}: {}", error_kind, message);
+    // The annotation already says it is a warning
+    let title = match error_kind {
+        ReportKind::Warning => message.to_string(),
+        _ => format!("{}: {}", error_kind, message),
+    };
     let filename = format!("{}:{}:{}", location.filename, location.row, location.column);
     let snippet = Snippet {
         title: Some(Annotation {
@@ -121,5 +167,10 @@ pub fn report(
         },
     };
 
-    FluetError(DisplayList::from(snippet).to_string())
+    FluetError {
+        kind: error_kind,
+        message: message.to_string(),
+        location: location.clone(),
+        report: DisplayList::from(snippet).to_string(),
+    }
 }
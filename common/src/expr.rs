@@ -6,19 +6,122 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use crate::stmt::Stmt;
-use crate::token::{Literal, Token};
+use std::fmt;
+use std::rc::Rc;
+
+use crate::stmt::{Function, Stmt};
+use crate::token::{Literal, Token, TokenType};
 
 #[derive(Debug, Clone)]
 pub enum Expr {
     Assignment(usize, Token, Box<Expr>),
     Binary(Box<Expr>, Token, Box<Expr>),
-    Block(Vec<Stmt>, Box<Expr>),
-    Call(Box<Expr>, Token, Vec<Expr>),
+    /// `{ statements; expr }`, with its opening and closing braces
+    Block(Token, Vec<Stmt>, Box<Expr>, Token),
+    Call(Box<Expr>, Token, Vec<Arg>),
+    Get(Box<Expr>, Token, Token),
     Grouping(Box<Expr>),
-    If(Box<Expr>, Box<Expr>, Box<Expr>),
+    /// `if condition then a else b`, with the `if` keyword
+    If(Token, Box<Expr>, Box<Expr>, Box<Expr>),
+    Index(Box<Expr>, Token, Box<Expr>),
+    List(Vec<Expr>),
     Literal(Literal),
     Logical(Box<Expr>, Token, Box<Expr>),
+    Set(Box<Expr>, Token, Box<Expr>),
+    /// `spawn fn() { ... }`, which runs the function in a new fiber.
+    Spawn(Token, Rc<Function>),
+    Spread(Token, Box<Expr>),
+    This(usize, Token),
+    Tuple(Vec<Expr>),
     Unary(Token, Box<Expr>),
     Variable(usize, Token),
 }
+
+#[derive(Debug, Clone)]
+pub enum Arg {
+    Named(Token, Expr),
+    Positional(Expr),
+}
+
+impl Arg {
+    pub fn expr(&self) -> &Expr {
+        match self {
+            Arg::Named(_, expr) | Arg::Positional(expr) => expr,
+        }
+    }
+}
+
+/// Shows an expression the way it could be written in source, with the
+/// statements of blocks and functions elided.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Assignment(_, name, value) => write!(f, "{} = {}", name.lexeme(), value),
+            Expr::Binary(lhs, operator, rhs) | Expr::Logical(lhs, operator, rhs) => {
+                write!(f, "{} {} {}", lhs, operator.lexeme(), rhs)
+            },
+            Expr::Block(_, stmts, expr, _) => match (stmts.is_empty(), &**expr) {
+                (true, Expr::Literal(Literal::Null)) => write!(f, "{{}}"),
+                (true, expr) => write!(f, "{{ {} }}", expr),
+                (false, _) => write!(f, "{{ … }}"),
+            },
+            Expr::Call(callee, paren, args) => {
+                match paren.token_type() {
+                    TokenType::QuestionDot => write!(f, "{}?.(", callee)?,
+                    _ => write!(f, "{}(", callee)?,
+                }
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    match arg {
+                        Arg::Named(name, expr) => write!(f, "{}: {}", name.lexeme(), expr)?,
+                        Arg::Positional(expr) => write!(f, "{}", expr)?,
+                    }
+                }
+                write!(f, ")")
+            },
+            Expr::Get(object, operator, name) => write!(f, "{}{}{}", object, operator.lexeme(), name.lexeme()),
+            Expr::Grouping(expr) => write!(f, "({})", expr),
+            Expr::If(_, condition, then_branch, else_branch) => match &**else_branch {
+                Expr::Literal(Literal::Null) => write!(f, "if {} then {}", condition, then_branch),
+                else_branch => write!(f, "if {} then {} else {}", condition, then_branch, else_branch),
+            },
+            Expr::Index(object, _, index) => write!(f, "{}[{}]", object, index),
+            Expr::List(elements) => {
+                write!(f, "[")?;
+                write_list(f, elements)?;
+                write!(f, "]")
+            },
+            Expr::Literal(Literal::Bool(bool)) => write!(f, "{}", bool),
+            Expr::Literal(Literal::Null) => write!(f, "null"),
+            Expr::Literal(Literal::Number(number)) => write!(f, "{}", number),
+            Expr::Literal(Literal::String(string)) => write!(f, "{:?}", string),
+            Expr::Set(object, name, value) => write!(f, "{}.{} = {}", object, name.lexeme(), value),
+            Expr::Spawn(_, _) => write!(f, "spawn fn() {{ … }}"),
+            Expr::Spread(_, expr) => write!(f, "...{}", expr),
+            Expr::This(_, _) => write!(f, "this"),
+            Expr::Tuple(elements) => {
+                write!(f, "(")?;
+                write_list(f, elements)?;
+                match elements.len() {
+                    1 => write!(f, ",)"),
+                    _ => write!(f, ")"),
+                }
+            },
+            Expr::Unary(operator, expr) => write!(f, "{}{}", operator.lexeme(), expr),
+            Expr::Variable(_, name) => write!(f, "{}", name.lexeme()),
+        }
+    }
+}
+
+fn write_list(f: &mut fmt::Formatter<'_>, exprs: &[Expr]) -> fmt::Result {
+    for (i, expr) in exprs.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", expr)?;
+    }
+
+    Ok(())
+}
@@ -14,15 +14,19 @@ lazy_static! {
     pub static ref KEYWORDS: HashMap<&'static str, TokenType> = {
         let mut keywords = HashMap::new();
         keywords.insert("and",    TokenType::LogicalAnd);
+        keywords.insert("catch",  TokenType::Catch);
         keywords.insert("class",  TokenType::Class);
         keywords.insert("const",  TokenType::Const);
         keywords.insert("else",   TokenType::Else);
         keywords.insert("enum",   TokenType::Enum);
         keywords.insert("false",  TokenType::False);
+        keywords.insert("finally", TokenType::Finally);
         keywords.insert("fn",     TokenType::Fn);
         keywords.insert("for",    TokenType::For);
         keywords.insert("if",     TokenType::If);
+        keywords.insert("in",     TokenType::In);
         keywords.insert("inf",    TokenType::Number);
+        keywords.insert("interface", TokenType::Interface);
         keywords.insert("let",    TokenType::Let);
         keywords.insert("loop",   TokenType::Loop);
         keywords.insert("match",  TokenType::Match);
@@ -31,11 +35,15 @@ lazy_static! {
         keywords.insert("null",   TokenType::Null);
         keywords.insert("or",     TokenType::LogicalOr);
         keywords.insert("return", TokenType::Return);
+        keywords.insert("spawn",  TokenType::Spawn);
         keywords.insert("super",  TokenType::Super);
         keywords.insert("then",   TokenType::Then);
         keywords.insert("this",   TokenType::This);
+        keywords.insert("throw",  TokenType::Throw);
         keywords.insert("true",   TokenType::True);
+        keywords.insert("try",    TokenType::Try);
         keywords.insert("while",  TokenType::While);
+        keywords.insert("yield",  TokenType::Yield);
         keywords
     };
 }
@@ -13,6 +13,8 @@ pub mod errors;
 pub mod expr;
 pub mod keywords;
 pub mod location;
+pub mod pattern;
 pub mod stmt;
 pub mod token;
+pub mod types;
 pub mod util;
@@ -0,0 +1,74 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+
+use crate::expr::Expr;
+use crate::token::{Token, TokenType};
+
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// `[a, b = default, ...rest]`, or `(a, b)` when the token is a
+    /// parenthesis. Either form destructures both lists and tuples.
+    List(Token, Vec<(Pattern, Option<Expr>)>, Option<Token>),
+    Name(Token),
+    /// `{a, b: pattern, c = default}`, destructured through property access
+    Object(Token, Vec<(Token, Pattern, Option<Expr>)>),
+}
+
+impl Pattern {
+    pub fn token(&self) -> &Token {
+        match self {
+            Pattern::List(token, _, _) | Pattern::Name(token) | Pattern::Object(token, _) => token,
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pattern::List(bracket, elements, rest) => {
+                let (open, close) = match bracket.token_type() {
+                    TokenType::LeftParen => ("(", ")"),
+                    _ => ("[", "]"),
+                };
+
+                write!(f, "{}", open)?;
+                for (i, (pattern, _)) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", pattern)?;
+                }
+                if let Some(rest) = rest {
+                    if !elements.is_empty() {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "...{}", rest.lexeme())?;
+                }
+                write!(f, "{}", close)
+            },
+            Pattern::Name(name) => write!(f, "{}", name.lexeme()),
+            Pattern::Object(_, fields) => {
+                write!(f, "{{")?;
+                for (i, (key, pattern, _)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    match pattern {
+                        Pattern::Name(name) if name.lexeme() == key.lexeme() => {
+                            write!(f, "{}", key.lexeme())?
+                        },
+                        pattern => write!(f, "{}: {}", key.lexeme(), pattern)?,
+                    }
+                }
+                write!(f, "}}")
+            },
+        }
+    }
+}
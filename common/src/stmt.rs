@@ -6,14 +6,113 @@
  * file, You can obtain one at https://mozilla.org/MPL/2.0/.
  */
 
-use crate::{expr::Expr, token::Token};
+use std::rc::Rc;
+
+use crate::{expr::Expr, pattern::Pattern, token::{Literal, Token}, types::TypeExpr};
 
 #[derive(Debug, Clone)]
 pub enum Stmt {
+    /// `class Name: Interface, ... { methods }`, with an id for the variables
+    /// its methods capture
+    Class(usize, Token, Vec<Expr>, Vec<Rc<Function>>, Option<Rc<Doc>>, Vec<Attribute>),
     Expr(Expr),
-    Fn(Token, Vec<Token>, Vec<Stmt>, Expr),
-    Let(Token, Expr),
+    Fn(Rc<Function>),
+    For(Pattern, Expr, Vec<Stmt>),
+    /// `interface Name { fn method(params); ... }`
+    Interface(Token, Vec<Signature>, Option<Rc<Doc>>),
+    Let(Pattern, Option<TypeExpr>, Expr, Option<Rc<Doc>>),
     Loop(Vec<Stmt>),
-    Return(Expr),
-    While(Expr, Vec<Stmt>),
+    Return(Token, Expr),
+    Throw(Token, Expr),
+    Try(Vec<Stmt>, Option<(Token, Vec<Stmt>)>, Option<Vec<Stmt>>),
+    /// `while condition { body }`, with the `while` keyword
+    While(Token, Expr, Vec<Stmt>),
+    Yield(Token, Expr),
+}
+
+#[derive(Debug, Clone)]
+pub struct Function {
+    /// Identifies the function to the resolver, like an expression id.
+    pub id: usize,
+    pub name: Token,
+    pub params: Vec<Param>,
+    /// The annotated return type, after `->`
+    pub returns: Option<TypeExpr>,
+    pub body: Vec<Stmt>,
+    /// The opening and closing braces of the body.
+    pub braces: (Token, Token),
+    /// The trailing expression of the body, evaluated when the body doesn't
+    /// return.
+    pub ret: Expr,
+    /// Whether the body contains a `yield`, making calls to this function
+    /// return a generator.
+    pub generator: bool,
+    pub doc: Option<Rc<Doc>>,
+    pub attributes: Vec<Attribute>,
+}
+
+impl Function {
+    /// The attribute called `name`, if the function has one.
+    pub fn attribute(&self, name: &str) -> Option<&Attribute> {
+        attribute(&self.attributes, name)
+    }
+}
+
+/// A method that an interface requires, without a body.
+#[derive(Debug, Clone)]
+pub struct Signature {
+    pub name: Token,
+    pub params: Vec<Param>,
+    pub returns: Option<TypeExpr>,
+    pub doc: Option<Rc<Doc>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub pattern: Pattern,
+    pub annotation: Option<TypeExpr>,
+    pub default: Option<Expr>,
+    pub rest: bool,
+}
+
+/// Documentation from the `///` or `/** */` comments before a declaration.
+#[derive(Debug, Clone)]
+pub struct Doc {
+    /// The first comment, which locates the documentation.
+    pub token: Token,
+    pub text: String,
+}
+
+/// An `@name` or `@name(arguments)` before a declaration.
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub name: Token,
+    /// The arguments, which can only be literals.
+    pub args: Vec<Literal>,
+}
+
+impl Attribute {
+    pub fn name(&self) -> &str {
+        self.name.lexeme()
+    }
+}
+
+impl Stmt {
+    /// The attributes of a function or class declaration.
+    pub fn attributes(&self) -> &[Attribute] {
+        match self {
+            Stmt::Class(_, _, _, _, _, attributes) => attributes,
+            Stmt::Fn(function) => &function.attributes,
+            _ => &[],
+        }
+    }
+
+    /// The attribute called `name`, if the declaration has one.
+    pub fn attribute(&self, name: &str) -> Option<&Attribute> {
+        attribute(self.attributes(), name)
+    }
+}
+
+fn attribute<'a>(attributes: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+    attributes.iter().find(|attribute| attribute.name() == name)
 }
@@ -77,23 +77,32 @@ impl fmt::Display for Token {
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TokenType {
     // Single-character tokens
-    LeftParen, RightParen, LeftBrace, RightBrace,
-    Comma, Dot, Minus, Percent, Plus, Semicolon, Slash, Star,
+    LeftParen, RightParen, LeftBrace, RightBrace, LeftBracket, RightBracket,
+    At, Comma, Dot, Percent, Plus, Semicolon, Slash, Star,
 
     // One or two character tokens
     Bang, BangEqual,
+    Minus, MinusGreater,
     Equal, EqualEqual,
     Greater, GreaterEqual,
     Less, LessEqual,
     Colon, ColonColon,
+    QuestionDot, QuestionQuestion,
+    PipeGreater,
+
+    // Three character tokens
+    DotDotDot,
 
     // Literals
     Identifier, String, Number,
 
+    /// A `///` or `/** */` comment, with its text as a string literal
+    DocComment,
+
     // Keywords
-    BitwiseAnd, BitwiseOr, Class, Const, Else, Enum, False, Fn, For, If,
-    Let, Loop, LogicalAnd, LogicalOr, Match, Module, Null, Return, Super, Then,
-    This, True, While,
+    BitwiseAnd, BitwiseOr, Catch, Class, Const, Else, Enum, False, Finally, Fn,
+    For, If, In, Interface, Let, Loop, LogicalAnd, LogicalOr, Match, Module, Null,
+    Return, Spawn, Super, Then, This, Throw, True, Try, While, Yield,
 
     EOF
 }
@@ -105,6 +114,9 @@ impl fmt::Display for TokenType {
             TokenType::RightParen => write!(f, ")"),
             TokenType::LeftBrace => write!(f, "{{"),
             TokenType::RightBrace => write!(f, "}}"),
+            TokenType::LeftBracket => write!(f, "["),
+            TokenType::RightBracket => write!(f, "]"),
+            TokenType::At => write!(f, "@"),
             TokenType::Comma => write!(f, ","),
             TokenType::Dot => write!(f, "."),
             TokenType::Minus => write!(f, "-"),
@@ -115,6 +127,7 @@ impl fmt::Display for TokenType {
             TokenType::Star => write!(f, "*"),
             TokenType::Bang => write!(f, "!"),
             TokenType::BangEqual => write!(f, "!="),
+            TokenType::MinusGreater => write!(f, "->"),
             TokenType::Equal => write!(f, "="),
             TokenType::EqualEqual => write!(f, "=="),
             TokenType::Greater => write!(f, ">"),
@@ -123,15 +136,23 @@ impl fmt::Display for TokenType {
             TokenType::LessEqual => write!(f, "<="),
             TokenType::Colon => write!(f, ":"),
             TokenType::ColonColon => write!(f, "::"),
+            TokenType::QuestionDot => write!(f, "?."),
+            TokenType::QuestionQuestion => write!(f, "??"),
+            TokenType::PipeGreater => write!(f, "|>"),
+            TokenType::DotDotDot => write!(f, "..."),
             TokenType::BitwiseAnd => write!(f, "&"),
             TokenType::BitwiseOr => write!(f, "|"),
+            TokenType::Catch => write!(f, "catch"),
             TokenType::Class => write!(f, "class"),
             TokenType::Else => write!(f, "else"),
             TokenType::Enum => write!(f, "enum"),
             TokenType::False => write!(f, "false"),
+            TokenType::Finally => write!(f, "finally"),
             TokenType::Fn => write!(f, "fn"),
             TokenType::For => write!(f, "for"),
             TokenType::If => write!(f, "if"),
+            TokenType::In => write!(f, "in"),
+            TokenType::Interface => write!(f, "interface"),
             TokenType::Let => write!(f, "let"),
             TokenType::Loop => write!(f, "loop"),
             TokenType::LogicalAnd => write!(f, "&&"),
@@ -140,10 +161,14 @@ impl fmt::Display for TokenType {
             TokenType::Module => write!(f, "module"),
             TokenType::Null => write!(f, "null"),
             TokenType::Return => write!(f, "return"),
+            TokenType::Spawn => write!(f, "spawn"),
             TokenType::Super => write!(f, "super"),
             TokenType::This => write!(f, "this"),
+            TokenType::Throw => write!(f, "throw"),
             TokenType::True => write!(f, "true"),
+            TokenType::Try => write!(f, "try"),
             TokenType::While => write!(f, "while"),
+            TokenType::Yield => write!(f, "yield"),
             _ => write!(f, "{:?}", self),
         }
     }
@@ -0,0 +1,67 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+
+use crate::location::Location;
+use crate::token::Token;
+
+/// A type annotation, as written after a `:` or `->`.
+#[derive(Debug, Clone)]
+pub enum TypeExpr {
+    /// `fn(number, string) -> bool`
+    Fn(Token, Vec<TypeExpr>, Box<TypeExpr>),
+    /// `[number]`
+    List(Token, Box<TypeExpr>),
+    Name(Token),
+    /// `(number, string)`
+    Tuple(Token, Vec<TypeExpr>),
+}
+
+impl TypeExpr {
+    pub fn location(&self) -> &Location {
+        match self {
+            TypeExpr::Fn(token, _, _)
+            | TypeExpr::List(token, _)
+            | TypeExpr::Name(token)
+            | TypeExpr::Tuple(token, _) => token.location(),
+        }
+    }
+}
+
+impl fmt::Display for TypeExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeExpr::Fn(_, params, ret) => {
+                write!(f, "fn(")?;
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", param)?;
+                }
+                write!(f, ") -> {}", ret)
+            },
+            TypeExpr::List(_, element) => write!(f, "[{}]", element),
+            TypeExpr::Name(name) => write!(f, "{}", name.lexeme()),
+            TypeExpr::Tuple(_, elements) => {
+                write!(f, "(")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                if elements.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            },
+        }
+    }
+}
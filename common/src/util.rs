@@ -7,11 +7,11 @@
  */
 
 pub fn is_digit(c: &str) -> bool {
-    c.chars().nth(0).unwrap_or_default().is_ascii_digit()
+    c.chars().next().unwrap_or_default().is_ascii_digit()
 }
 
 pub fn is_alpha(c: &str) -> bool {
-    c.chars().nth(0).unwrap_or_default().is_ascii_alphabetic() || c == "_"
+    c.chars().next().unwrap_or_default().is_ascii_alphabetic() || c == "_"
 }
 
 pub fn is_alphanumeric(c: &str) -> bool {
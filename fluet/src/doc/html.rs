@@ -0,0 +1,123 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use pulldown_cmark::{html, Parser};
+
+use super::{Item, Links, Module, SECTIONS};
+
+const STYLE: &str = "\
+body { font-family: sans-serif; max-width: 50em; margin: 2em auto; padding: 0 1em; line-height: 1.5; }
+code, pre { font-family: monospace; background: #f4f4f4; }
+pre { padding: 0.5em; overflow-x: auto; }
+section { margin-bottom: 1.5em; }
+.members { margin-left: 1.5em; }
+a { color: #2a6496; text-decoration: none; }";
+
+pub fn index(modules: &[Module]) -> String {
+    let mut body = String::from("<h1>Modules</h1>\n<ul>\n");
+    for module in modules {
+        body.push_str(&format!(
+            "<li><a href=\"{}.html\">{}</a></li>\n",
+            module.page,
+            escape(&module.name)
+        ));
+    }
+    body.push_str("</ul>\n");
+
+    page("Modules", &body)
+}
+
+pub fn module(module: &Module, links: &Links) -> String {
+    let mut body = format!(
+        "<nav><a href=\"index.html\">Modules</a></nav>\n<h1>Module <code>{}</code></h1>\n",
+        escape(&module.name)
+    );
+
+    for kind in SECTIONS {
+        let items: Vec<&Item> = module.items.iter().filter(|item| item.kind == kind).collect();
+        if items.is_empty() {
+            continue;
+        }
+
+        body.push_str(&format!("<h2>{}</h2>\n", kind.heading()));
+        for item in items {
+            body.push_str(&format!("<section id=\"{}\">\n", item.anchor()));
+            body.push_str(&format!("<h3><code>{}</code></h3>\n", signature(item, links)));
+            body.push_str(&doc(item, links));
+
+            if !item.members.is_empty() {
+                body.push_str("<div class=\"members\">\n");
+                for member in &item.members {
+                    body.push_str(&format!(
+                        "<h4 id=\"{}\"><code>{}</code></h4>\n",
+                        item.member_anchor(member),
+                        signature(member, links)
+                    ));
+                    body.push_str(&doc(member, links));
+                }
+                body.push_str("</div>\n");
+            }
+
+            body.push_str("</section>\n");
+        }
+    }
+
+    page(&module.name, &body)
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>\n{body}</body>\n</html>\n",
+        escape(title)
+    )
+}
+
+/// Renders a signature, linking the classes and interfaces it names.
+fn signature(item: &Item, links: &Links) -> String {
+    let mut html = String::new();
+    let mut word = String::new();
+
+    for c in item.signature.chars().chain(std::iter::once('\0')) {
+        if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+
+        if !word.is_empty() {
+            match links.type_url(&word, "html") {
+                Some(url) if word != item.name => html.push_str(&format!("<a href=\"{url}\">{word}</a>")),
+                _ => html.push_str(&escape(&word)),
+            }
+            word.clear();
+        }
+
+        if c != '\0' {
+            html.push_str(&escape(&c.to_string()));
+        }
+    }
+
+    html
+}
+
+fn doc(item: &Item, links: &Links) -> String {
+    let text = match &item.doc {
+        Some(text) => links.link_doc(text, "html"),
+        None => return String::new(),
+    };
+
+    let mut output = String::new();
+    html::push_html(&mut output, Parser::new(&text));
+    output
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
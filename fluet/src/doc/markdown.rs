@@ -0,0 +1,67 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use super::{Item, Links, Module, SECTIONS};
+
+pub fn index(modules: &[Module]) -> String {
+    let mut markdown = String::from("# Modules\n\n");
+    for module in modules {
+        markdown.push_str(&format!("- [{}]({}.md)\n", module.name, module.page));
+    }
+
+    markdown
+}
+
+pub fn module(module: &Module, links: &Links) -> String {
+    let mut markdown = format!("[Modules](index.md)\n\n# Module `{}`\n", module.name);
+
+    for kind in SECTIONS {
+        let items: Vec<&Item> = module.items.iter().filter(|item| item.kind == kind).collect();
+        if items.is_empty() {
+            continue;
+        }
+
+        markdown.push_str(&format!("\n## {}\n", kind.heading()));
+        for item in items {
+            markdown.push_str(&format!("\n<a id=\"{}\"></a>\n### `{}`\n", item.anchor(), item.signature));
+
+            if !item.interfaces.is_empty() {
+                let interfaces: Vec<String> = item
+                    .interfaces
+                    .iter()
+                    .map(|interface| match links.type_url(interface, "md") {
+                        Some(url) => format!("[{interface}]({url})"),
+                        None => interface.clone(),
+                    })
+                    .collect();
+
+                markdown.push_str(&format!("\nImplements: {}\n", interfaces.join(", ")));
+            }
+
+            markdown.push_str(&doc(item, links));
+
+            for member in &item.members {
+                markdown.push_str(&format!(
+                    "\n<a id=\"{}\"></a>\n#### `{}`\n",
+                    item.member_anchor(member),
+                    member.signature
+                ));
+                markdown.push_str(&doc(member, links));
+            }
+        }
+    }
+
+    markdown
+}
+
+fn doc(item: &Item, links: &Links) -> String {
+    match &item.doc {
+        Some(text) => format!("\n{}\n", links.link_doc(text, "md")),
+        None => String::new(),
+    }
+}
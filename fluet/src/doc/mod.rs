@@ -0,0 +1,347 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod html;
+mod markdown;
+pub mod test;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use anyhow::Result;
+use common::expr::Expr;
+use common::stmt::{Doc, Function, Param, Stmt};
+use common::types::TypeExpr;
+use lexer::Lexer;
+use parser::Parser;
+
+/// A documented source file.
+pub struct Module {
+    /// The path of the file relative to the documented directory, like
+    /// `shapes::circle` for `shapes/circle.fl`.
+    pub name: String,
+    /// The name of the pages generated for the module, without an extension.
+    pub page: String,
+    pub items: Vec<Item>,
+}
+
+pub struct Item {
+    pub kind: ItemKind,
+    pub name: String,
+    pub signature: String,
+    /// The interfaces a class declares it implements.
+    pub interfaces: Vec<String>,
+    pub doc: Option<String>,
+    /// The methods of a class or interface.
+    pub members: Vec<Item>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Class,
+    Function,
+    Interface,
+    Method,
+    Variable,
+}
+
+impl ItemKind {
+    /// The prefix of anchors to items of this kind.
+    fn prefix(&self) -> &'static str {
+        match self {
+            ItemKind::Class => "class",
+            ItemKind::Function => "fn",
+            ItemKind::Interface => "interface",
+            ItemKind::Method => "method",
+            ItemKind::Variable => "let",
+        }
+    }
+
+    fn heading(&self) -> &'static str {
+        match self {
+            ItemKind::Class => "Classes",
+            ItemKind::Function => "Functions",
+            ItemKind::Interface => "Interfaces",
+            ItemKind::Method => "Methods",
+            ItemKind::Variable => "Variables",
+        }
+    }
+
+    /// Whether items of this kind can be named in type annotations.
+    fn is_type(&self) -> bool {
+        matches!(self, ItemKind::Class | ItemKind::Interface)
+    }
+}
+
+impl Item {
+    pub fn anchor(&self) -> String {
+        format!("{}.{}", self.kind.prefix(), self.name)
+    }
+
+    /// The anchor of a method, qualified by its class or interface.
+    pub fn member_anchor(&self, member: &Item) -> String {
+        format!("{}.{}", self.anchor(), member.name)
+    }
+}
+
+/// Where every top-level item is documented, for links between them.
+pub struct Links {
+    items: HashMap<String, (String, ItemKind)>,
+}
+
+impl Links {
+    fn new(modules: &[Module]) -> Self {
+        let mut items = HashMap::new();
+        for module in modules {
+            for item in &module.items {
+                // The first item with a name gets the links to it
+                items
+                    .entry(item.name.clone())
+                    .or_insert_with(|| (format!("{}.{{ext}}#{}", module.page, item.anchor()), item.kind));
+            }
+        }
+
+        Self { items }
+    }
+
+    /// The URL of the page and anchor an item is documented at.
+    pub fn url(&self, name: &str, extension: &str) -> Option<String> {
+        let (url, _) = self.items.get(name)?;
+        Some(url.replace("{ext}", extension))
+    }
+
+    /// The URL of a class or interface named in a signature.
+    pub fn type_url(&self, name: &str, extension: &str) -> Option<String> {
+        match self.items.get(name) {
+            Some((_, kind)) if kind.is_type() => self.url(name, extension),
+            _ => None,
+        }
+    }
+
+    /// Turns `[Name]` in documentation into a link to the item called `Name`,
+    /// leaving fenced code blocks and ordinary Markdown links alone.
+    pub fn link_doc(&self, text: &str, extension: &str) -> String {
+        let mut fenced = false;
+        let mut lines = vec![];
+
+        for line in text.lines() {
+            if line.trim_start().starts_with("```") {
+                fenced = !fenced;
+            }
+
+            if fenced {
+                lines.push(line.to_string());
+                continue;
+            }
+
+            let mut linked = String::new();
+            let mut rest = line;
+            while let Some(start) = rest.find('[') {
+                linked.push_str(&rest[..start]);
+                rest = &rest[start..];
+
+                let end = match rest.find(']') {
+                    Some(end) => end,
+                    None => break,
+                };
+
+                let name = rest[1..end].trim_matches('`');
+                let is_link = matches!(rest[end + 1..].chars().next(), Some('(' | '[' | ':'));
+                match self.url(name, extension) {
+                    Some(url) if !is_link => linked.push_str(&format!("{}({url})", &rest[..=end])),
+                    _ => linked.push_str(&rest[..=end]),
+                }
+
+                rest = &rest[end + 1..];
+            }
+
+            linked.push_str(rest);
+            lines.push(linked);
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Writes HTML and Markdown documentation for a file, or every `.fl` file in
+/// a directory, to `out`.
+pub fn generate(path: &Path, out: &Path) -> Result<()> {
+    let mut modules = vec![];
+    if path.is_dir() {
+        for file in source_files(path)? {
+            let relative = file.strip_prefix(path)?.with_extension("");
+            let parts: Vec<String> = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy().into_owned())
+                .collect();
+
+            modules.push(document(&file, parts.join("::"), parts.join("."))?);
+        }
+    } else {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "main".to_string());
+
+        modules.push(document(path, name.clone(), name)?);
+    }
+
+    let links = Links::new(&modules);
+    fs::create_dir_all(out)?;
+    fs::write(out.join("index.html"), html::index(&modules))?;
+    fs::write(out.join("index.md"), markdown::index(&modules))?;
+
+    for module in &modules {
+        fs::write(out.join(format!("{}.html", module.page)), html::module(module, &links))?;
+        fs::write(out.join(format!("{}.md", module.page)), markdown::module(module, &links))?;
+    }
+
+    Ok(())
+}
+
+fn source_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            files.extend(source_files(&path)?);
+        } else if path.extension().is_some_and(|extension| extension == "fl") {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+fn document(path: &Path, name: String, page: String) -> Result<Module> {
+    let source = fs::read_to_string(path)?;
+    let mut lexer = Lexer::new(source, path.to_string_lossy().into_owned());
+    let tokens = lexer.scan_tokens();
+
+    let statements = match Parser::new(tokens.to_vec()).parse() {
+        Ok(statements) => statements,
+        Err(err) => bail!(err),
+    };
+
+    Ok(Module { name, page, items: statements.iter().filter_map(item).collect() })
+}
+
+fn item(stmt: &Stmt) -> Option<Item> {
+    match stmt {
+        Stmt::Class(_, name, interfaces, methods, doc, _) => {
+            let interfaces: Vec<String> = interfaces
+                .iter()
+                .map(|interface| match interface {
+                    Expr::Variable(_, name) => name.lexeme().to_string(),
+                    _ => String::new(),
+                })
+                .collect();
+
+            let signature = match interfaces.is_empty() {
+                true => format!("class {}", name.lexeme()),
+                false => format!("class {}: {}", name.lexeme(), interfaces.join(", ")),
+            };
+
+            Some(Item {
+                kind: ItemKind::Class,
+                name: name.lexeme().to_string(),
+                signature,
+                interfaces,
+                doc: text(doc),
+                members: methods.iter().map(|method| function(method, ItemKind::Method)).collect(),
+            })
+        },
+        Stmt::Fn(function_stmt) => Some(function(function_stmt, ItemKind::Function)),
+        Stmt::Interface(name, methods, doc) => Some(Item {
+            kind: ItemKind::Interface,
+            name: name.lexeme().to_string(),
+            signature: format!("interface {}", name.lexeme()),
+            interfaces: vec![],
+            doc: text(doc),
+            members: methods
+                .iter()
+                .map(|method| Item {
+                    kind: ItemKind::Method,
+                    name: method.name.lexeme().to_string(),
+                    signature: fn_signature(method.name.lexeme(), &method.params, &method.returns),
+                    interfaces: vec![],
+                    doc: text(&method.doc),
+                    members: vec![],
+                })
+                .collect(),
+        }),
+        // Only documented variables are part of the API
+        Stmt::Let(pattern, annotation, _, doc @ Some(_)) => Some(Item {
+            kind: ItemKind::Variable,
+            name: pattern.to_string(),
+            signature: match annotation {
+                Some(annotation) => format!("let {pattern}: {annotation}"),
+                None => format!("let {pattern}"),
+            },
+            interfaces: vec![],
+            doc: text(doc),
+            members: vec![],
+        }),
+        _ => None,
+    }
+}
+
+fn function(function: &Function, kind: ItemKind) -> Item {
+    Item {
+        kind,
+        name: function.name.lexeme().to_string(),
+        signature: fn_signature(function.name.lexeme(), &function.params, &function.returns),
+        interfaces: vec![],
+        doc: text(&function.doc),
+        members: vec![],
+    }
+}
+
+fn fn_signature(name: &str, params: &[Param], returns: &Option<TypeExpr>) -> String {
+    let params: Vec<String> = params
+        .iter()
+        .map(|param| {
+            let mut signature = match param.rest {
+                true => format!("...{}", param.pattern),
+                false => param.pattern.to_string(),
+            };
+
+            if let Some(annotation) = &param.annotation {
+                signature.push_str(&format!(": {annotation}"));
+            }
+            if param.default.is_some() {
+                signature.push_str(" = …");
+            }
+
+            signature
+        })
+        .collect();
+
+    match returns {
+        Some(returns) => format!("fn {name}({}) -> {returns}", params.join(", ")),
+        None => format!("fn {name}({})", params.join(", ")),
+    }
+}
+
+fn text(doc: &Option<Rc<Doc>>) -> Option<String> {
+    doc.as_ref().map(|doc| doc.text.clone())
+}
+
+/// The kinds of top-level items in the order they are listed.
+const SECTIONS: [ItemKind; 4] = [
+    ItemKind::Interface,
+    ItemKind::Class,
+    ItemKind::Function,
+    ItemKind::Variable,
+];
@@ -0,0 +1,154 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use common::expr::Expr;
+use common::stmt::Stmt;
+use common::token::{Literal, TokenType};
+use interpreter::Interpreter;
+use lexer::Lexer;
+use parser::Parser;
+use resolver::Resolver;
+
+/// A fenced code block in a doc comment.
+struct DocTest {
+    /// The line of the source file the code starts on.
+    row: usize,
+    code: String,
+}
+
+/// Runs the code blocks in the doc comments of a file, or every `.fl` file in
+/// a directory, each with a fresh interpreter that has the declarations of the
+/// file defined. Returns whether all of them passed.
+pub fn run(path: &Path) -> Result<bool> {
+    let files = match path.is_dir() {
+        true => super::source_files(path)?,
+        false => vec![path.to_path_buf()],
+    };
+
+    let (mut passed, mut failed) = (0, 0);
+    for file in files {
+        let filename = file.to_string_lossy().into_owned();
+        let source = fs::read_to_string(&file)?;
+        let declarations = declarations(source.clone(), &filename)?;
+
+        for test in doc_tests(source, &filename) {
+            match run_test(&test, &filename, &declarations) {
+                Ok(()) => {
+                    println!("test {filename}:{} ... ok", test.row);
+                    passed += 1;
+                }
+                Err(err) => {
+                    println!("test {filename}:{} ... FAILED", test.row);
+                    eprintln!("{err}");
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    println!("\n{passed} passed; {failed} failed");
+    Ok(failed == 0)
+}
+
+fn doc_tests(source: String, filename: &str) -> Vec<DocTest> {
+    let mut lexer = Lexer::new(source, filename.to_string());
+
+    // Every line of documentation, with the line of the file it is on
+    let mut lines = vec![];
+    for token in lexer.scan_tokens() {
+        let text = match (token.token_type(), token.literal()) {
+            (TokenType::DocComment, Some(Literal::String(text))) => text,
+            _ => continue,
+        };
+
+        // A `/** */` comment starts where its delimiter is, which may be on a
+        // line of its own
+        let row = token.location().row + token
+            .lexeme()
+            .lines()
+            .position(|line| !line.trim().trim_start_matches("/**").trim_start_matches('*').trim().is_empty())
+            .unwrap_or(0);
+
+        lines.extend(text.lines().enumerate().map(|(i, line)| (row + i, line.to_string())));
+    }
+
+    let mut tests = vec![];
+    let mut current: Option<(bool, DocTest)> = None;
+    for (row, line) in lines {
+        let fence = line.trim_start().strip_prefix("```").map(str::trim);
+        match (&mut current, fence) {
+            (None, Some(info)) => {
+                // Blocks in other languages are only shown
+                let runs = matches!(info, "" | "fl" | "fluet");
+                current = Some((runs, DocTest { row: row + 1, code: String::new() }));
+            }
+            (Some(_), Some(_)) => {
+                if let Some((true, test)) = current.take() {
+                    tests.push(test);
+                }
+            }
+            (Some((_, test)), None) => {
+                test.code.push_str(&line);
+                test.code.push('\n');
+            }
+            (None, None) => {}
+        }
+    }
+
+    tests
+}
+
+/// The functions, classes and interfaces a file declares, and its top-level
+/// variables that are set to literals. Other statements, and initializers
+/// that could have side effects, aren't run before every doc test.
+fn declarations(source: String, filename: &str) -> Result<Vec<Stmt>> {
+    let mut lexer = Lexer::new(source, filename.to_string());
+    let tokens = lexer.scan_tokens();
+
+    let statements = match Parser::new(tokens.to_vec()).parse() {
+        Ok(statements) => statements,
+        Err(err) => bail!(err),
+    };
+
+    Ok(statements
+        .into_iter()
+        .filter(|stmt| matches!(stmt,
+            Stmt::Class(..) | Stmt::Fn(_) | Stmt::Interface(..) | Stmt::Let(_, _, Expr::Literal(_), _)
+        ))
+        .collect())
+}
+
+fn run_test(test: &DocTest, filename: &str, declarations: &[Stmt]) -> Result<()> {
+    // Pad the code so errors point at the lines of the original file
+    let code = "\n".repeat(test.row - 1) + &test.code;
+    let mut lexer = Lexer::new(code, filename.to_string());
+    let tokens = lexer.scan_tokens();
+
+    let statements = match Parser::new(tokens.to_vec()).parse() {
+        Ok(statements) => statements,
+        Err(err) => bail!(err),
+    };
+
+    let mut interpreter = Interpreter::new();
+    let mut resolver = Resolver::new();
+    resolver.resolve(&declarations.to_vec(), &mut interpreter)?;
+    resolver.resolve(&statements, &mut interpreter)?;
+
+    for declaration in declarations {
+        interpreter.execute(declaration)?;
+    }
+
+    match interpreter.interpret(statements) {
+        Ok(()) => Ok(()),
+        Err(err) => bail!(err),
+    }
+}
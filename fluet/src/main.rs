@@ -15,13 +15,16 @@ use std::io::{Read, Write};
 use std::{fs::File, path::Path};
 
 use anyhow::Result;
+use checker::Checker;
 use clap::{App, Arg};
 use colored::*;
 use interpreter::Interpreter;
 use interpreter::value::Value;
 use lexer::Lexer;
 use parser::Parser;
-use resolver::Resolver;
+use resolver::{Lint, Resolver};
+
+mod doc;
 
 fn main() {
     let matches = App::new("fluet")
@@ -36,24 +39,84 @@ fn main() {
                 .long("dump-tokens")
                 .short('D')
                 .help("Dumps the tokens before running"),
+            Arg::new("no-typecheck")
+                .long("no-typecheck")
+                .help("Skips checking type annotations before running"),
+            Arg::new("allow")
+                .long("allow")
+                .short('A')
+                .takes_value(true)
+                .multiple_occurrences(true)
+                .possible_values(Lint::ALL.map(|lint| lint.name()))
+                .help("Turns off a lint"),
             arg!([FILE] "File to be run")
         ])
+        .subcommand(App::new("doc")
+            .about("Generates HTML and Markdown documentation from doc comments")
+            .args([
+                arg!(<PATH> "File or directory to document"),
+                Arg::new("out")
+                    .long("out")
+                    .short('o')
+                    .takes_value(true)
+                    .default_value("doc")
+                    .help("Directory to write the documentation to"),
+            ]))
+        .subcommand(App::new("test")
+            .about("Runs the code blocks in doc comments")
+            .arg(arg!(<PATH> "File or directory to test")))
         .get_matches();
 
+    match matches.subcommand() {
+        Some(("doc", matches)) => {
+            // Both values are required or defaulted
+            let path = matches.value_of("PATH").unwrap();
+            let out = matches.value_of("out").unwrap();
+            if let Err(err) = doc::generate(Path::new(path), Path::new(out)) {
+                eprintln!("{}", err);
+            }
+
+            return;
+        }
+        Some(("test", matches)) => {
+            match doc::test::run(Path::new(matches.value_of("PATH").unwrap())) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+
+            return;
+        }
+        _ => {}
+    }
+
+    // Only known names get past `possible_values`
+    let allowed: Vec<Lint> = matches
+        .values_of("allow")
+        .map(|names| names.filter_map(Lint::from_name).collect())
+        .unwrap_or_default();
+
     let mut interpreter = Interpreter::new();
     if let Some(file) = matches.value_of("FILE") {
         if let Err(err) = run_file(
             file,
             &mut interpreter,
             matches.is_present("dump-ast"),
-            matches.is_present("dump-tokens")
+            matches.is_present("dump-tokens"),
+            !matches.is_present("no-typecheck"),
+            &allowed
         ) {
             eprintln!("{}", err);
         }
     } else if let Err(err) = run_prompt(
         &mut interpreter,
         matches.is_present("dump-ast"),
-        matches.is_present("dump-tokens")
+        matches.is_present("dump-tokens"),
+        !matches.is_present("no-typecheck"),
+        &allowed
     ) {
         eprintln!("{}", err);
     }
@@ -62,7 +125,9 @@ fn main() {
 fn run_file<P>(path: P,
     interpreter: &mut Interpreter,
     dump_ast: bool,
-    dump_tokens: bool) -> Result<()>
+    dump_tokens: bool,
+    typecheck: bool,
+    allowed: &[Lint]) -> Result<()>
 where
     P: AsRef<Path>,
 {
@@ -78,7 +143,9 @@ where
             .to_string(),
         interpreter,
         dump_ast,
-        dump_tokens
+        dump_tokens,
+        typecheck,
+        allowed
     ) {
         Ok(_) => {}
         Err(err) => eprintln!("{}", err),
@@ -88,7 +155,9 @@ where
 
 fn run_prompt(interpreter: &mut Interpreter,
     dump_ast: bool,
-    dump_tokens: bool) -> Result<()>
+    dump_tokens: bool,
+    typecheck: bool,
+    allowed: &[Lint]) -> Result<()>
 {
     let mut contents = String::new();
 
@@ -102,7 +171,9 @@ fn run_prompt(interpreter: &mut Interpreter,
             "<repl>".green().italic().to_string(),
             interpreter,
             dump_ast,
-            dump_tokens
+            dump_tokens,
+            typecheck,
+            allowed
         ) {
             Ok(value) => println!("{}", value),
             Err(err) => eprintln!("{}", err),
@@ -116,7 +187,9 @@ fn run(code: String,
     filename: String,
     interpreter: &mut Interpreter,
     dump_ast: bool,
-    dump_tokens: bool) -> Result<()>
+    dump_tokens: bool,
+    typecheck: bool,
+    allowed: &[Lint]) -> Result<()>
 {
     let mut lexer = Lexer::new(code, filename.clone());
     let tokens = lexer.scan_tokens();
@@ -137,7 +210,21 @@ fn run(code: String,
     }
 
     let mut resolver = Resolver::new();
+    for &lint in allowed {
+        resolver.allow(lint);
+    }
     resolver.resolve(&statements, interpreter)?;
+    for warning in resolver.take_warnings() {
+        eprintln!("{}", warning);
+    }
+
+    if typecheck {
+        let mut checker = Checker::new();
+        checker.check(&statements)?;
+        for warning in checker.take_warnings() {
+            eprintln!("{}", warning);
+        }
+    }
 
     match interpreter.interpret(statements) {
         Ok(value) => Ok(value),
@@ -149,7 +236,9 @@ fn eval(code: String,
     filename: String,
     interpreter: &mut Interpreter,
     dump_ast: bool,
-    dump_tokens: bool) -> Result<Value>
+    dump_tokens: bool,
+    typecheck: bool,
+    allowed: &[Lint]) -> Result<Value>
 {
     let mut lexer = Lexer::new(code, filename.clone());
     let tokens = lexer.scan_tokens();
@@ -164,10 +253,30 @@ fn eval(code: String,
     }
 
     let mut resolver = Resolver::new();
+    for &lint in allowed {
+        resolver.allow(lint);
+    }
     resolver.resolve(&statements, interpreter)?;
+    for warning in resolver.take_warnings() {
+        eprintln!("{}", warning);
+    }
 
-    match interpreter.evaluate_block(&statements, &expr, false) {
-        Ok(value) => Ok(value),
+    if typecheck {
+        let mut checker = Checker::new();
+        checker.check(&statements)?;
+        checker.check_expr(&expr)?;
+        for warning in checker.take_warnings() {
+            eprintln!("{}", warning);
+        }
+    }
+
+    let value = match interpreter.evaluate_block(&statements, &expr, false) {
+        Ok(value) => value,
+        Err(err) => bail!(err),
+    };
+
+    match interpreter.run_fibers() {
+        Ok(()) => Ok(value),
         Err(err) => bail!(err),
     }
 }
@@ -0,0 +1,65 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::{fluet, run};
+
+#[test]
+fn defaults_are_evaluated_at_each_call() {
+    let output = run("defaults", r#"
+        let calls = 0;
+        fn next() { calls = calls + 1; calls }
+        fn connect(host, port = 8080, id = next()) {
+            print(host);
+            print(port);
+            print(id);
+        }
+
+        connect('db');
+        connect('db', 5432);
+        connect('db', 5432, 'fixed');
+        connect('db');
+    "#);
+
+    assert_eq!(output, "db\n8080\n1\ndb\n5432\n2\ndb\n5432\nfixed\ndb\n8080\n3\n");
+}
+
+#[test]
+fn named_arguments_fill_parameters_by_name() {
+    let output = run("named", r#"
+        fn connect(host, port = 8080, timeout = 30) {
+            print(host);
+            print(port);
+            print(timeout);
+        }
+
+        connect('db', timeout: 5);
+        connect(timeout: 1, host: 'cache');
+        connect('db', 1, timeout: 2);
+    "#);
+
+    assert_eq!(output, "db\n8080\n5\ncache\n8080\n1\ndb\n1\n2\n");
+}
+
+#[test]
+fn arity_errors_name_the_parameters() {
+    let output = fluet("missing", r#"
+        fn connect(host, port, timeout = 30) {}
+        connect(timeout: 1);
+    "#, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("No value given for parameters 'host', 'port'."), "{stderr}");
+
+    let output = fluet("unknown", r#"
+        fn connect(host) {}
+        connect('db', retries: 3);
+    "#, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Unknown parameter 'retries'.") && stderr.contains("unknown.fl:3:"), "{stderr}");
+}
@@ -0,0 +1,46 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::{fluet, run};
+
+#[test]
+fn passing_assertions_are_silent() {
+    let output = run("assert_pass", r#"
+        fn add(a, b) { a + b }
+
+        assert(add(1, 1) == 2);
+        assert_eq(add(1, 2), 3);
+        assert_ne("a", "b");
+        (1, 2) |> assert_eq((1, 2));
+
+        try {
+            assert(false);
+        } catch err {
+            print(err);
+        }
+    "#);
+
+    assert_eq!(output, "AssertionError: assertion failed: `false`\n");
+}
+
+#[test]
+fn failures_show_the_source_and_values() {
+    let output = fluet("assert_fail", r#"
+        fn add(a, b) { a + b }
+        let xs = [1, 2];
+        assert_eq(add(xs[0], 2), len(xs));
+    "#, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(stderr.contains("AssertionError: assert_eq failed: `add(xs[0], 2)` is not equal to `len(xs)`"), "{stderr}");
+    assert!(stderr.contains("  left: 3\n right: 2"), "{stderr}");
+    assert!(stderr.contains("assert_fail.fl:4:"), "{stderr}");
+    assert!(stderr.contains("assert_eq(add(xs[0], 2), len(xs));"), "{stderr}");
+}
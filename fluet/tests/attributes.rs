@@ -0,0 +1,78 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::fluet;
+
+#[test]
+fn calling_deprecated_functions_warns() {
+    let output = fluet("deprecated", r#"
+        @deprecated("use bar")
+        fn foo() { 1 }
+
+        @deprecated
+        class Old {}
+
+        print(foo());
+        Old();
+
+        // Not deprecated any more
+        fn foo() { 2 }
+        print(foo());
+    "#, &[]);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n2\n");
+    assert!(stderr.contains("foo is deprecated: use bar"), "{stderr}");
+    assert!(stderr.contains("Old is deprecated."), "{stderr}");
+    assert_eq!(stderr.matches("warning").count(), 2, "{stderr}");
+}
+
+#[test]
+fn deprecation_covers_earlier_code_and_methods() {
+    let output = fluet("deprecated_methods", r#"
+        fn a() { old() }
+
+        @deprecated("use new")
+        fn old() { 1 }
+
+        class C {
+            @deprecated
+            fn m() { 2 }
+            fn n() { 3 }
+        }
+
+        print(a());
+        print(C().m());
+        print(C().n());
+    "#, &[]);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n2\n3\n");
+    assert!(stderr.contains("old is deprecated: use new"), "{stderr}");
+    assert!(stderr.contains("m is deprecated."), "{stderr}");
+    assert!(!stderr.contains("Warning:"), "{stderr}");
+    assert_eq!(stderr.matches("warning").count(), 2, "{stderr}");
+}
+
+#[test]
+fn deprecation_warnings_can_be_allowed() {
+    let source = r#"
+        @deprecated
+        fn old() { 1 }
+        print(old());
+    "#;
+
+    let stderr = String::from_utf8(fluet("deprecated_lint", source, &[]).stderr).unwrap();
+    assert!(stderr.contains("warning[deprecated]"), "{stderr}");
+
+    let output = fluet("deprecated_allowed", source, &["-A", "deprecated"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n");
+    assert!(output.stderr.is_empty(), "{}", String::from_utf8(output.stderr).unwrap());
+}
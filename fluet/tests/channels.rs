@@ -0,0 +1,101 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::{fluet, run};
+
+#[test]
+fn fibers_run_in_the_order_they_are_spawned() {
+    let output = run("fiber_order", r#"
+        let c = chan();
+        spawn fn() { print('a1'); send(c, 1); print('a2'); };
+        spawn fn() { print('b1'); send(c, 2); print('b2'); };
+        print('main', recv(c), recv(c));
+    "#);
+
+    assert_eq!(output, "a1\nb1\nmain 1 2\na2\nb2\n");
+}
+
+#[test]
+fn sends_wait_to_be_received() {
+    let output = run("unbuffered", r#"
+        let c = chan();
+        spawn fn() {
+            send(c, 'ping');
+            print('sent');
+        };
+
+        print('before');
+        print(recv(c));
+        print('after');
+    "#);
+
+    assert_eq!(output, "before\nping\nafter\nsent\n");
+}
+
+#[test]
+fn deadlocks_are_reported_where_they_block() {
+    let output = fluet("deadlock_main", r#"
+        let c = chan();
+        spawn fn() { recv(c); };
+        print(recv(c));
+    "#, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Deadlock: every fiber is blocked."), "{stderr}");
+    assert!(stderr.contains("deadlock_main.fl:4:"), "{stderr}");
+
+    let output = fluet("deadlock_fibers", r#"
+        let c = chan();
+        spawn fn() { recv(c); };
+    "#, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("deadlock_fibers.fl:3:"), "{stderr}");
+
+    let output = fluet("not_a_channel", "send(1, 2);", &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("1 is not a channel") && stderr.contains(":1:"), "{stderr}");
+}
+
+#[test]
+fn fibers_have_room_to_recurse() {
+    let output = run("fiber_recursion", r#"
+        fn sum(n) { if n == 0 then 0 else n + sum(n - 1) }
+        spawn fn() { print(sum(200)); };
+    "#);
+
+    assert_eq!(output, "20100\n");
+
+    let output = fluet("fiber_overflow", r#"
+        fn forever(n) { 1 + forever(n + 1) }
+        spawn fn() { forever(0); };
+    "#, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Stack overflow: too many nested calls.") && stderr.contains("fiber_overflow.fl:2:"), "{stderr}");
+}
+
+#[test]
+fn generators_block_the_fiber_resuming_them() {
+    let output = run("fiber_generators", r#"
+        let c = chan();
+        fn received() {
+            yield recv(c);
+            yield recv(c);
+        }
+
+        spawn fn() {
+            for n in received() { print('got', n); }
+        };
+
+        send(c, 1);
+        send(c, 2);
+        print('sent');
+    "#);
+
+    assert_eq!(output, "got 1\ngot 2\nsent\n");
+}
@@ -0,0 +1,33 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Runs a script with the fluet binary and returns what it printed.
+#[allow(dead_code)]
+pub fn run(name: &str, source: &str) -> String {
+    let output = fluet(name, source, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.is_empty(), "{name} failed:\n{stderr}");
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Runs a script with the fluet binary and the given flags.
+#[allow(dead_code)]
+pub fn fluet(name: &str, source: &str, args: &[&str]) -> Output {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{name}.fl"));
+    fs::write(&path, source).unwrap();
+
+    Command::new(env!("CARGO_BIN_EXE_fluet"))
+        .args(args)
+        .arg(&path)
+        .output()
+        .unwrap()
+}
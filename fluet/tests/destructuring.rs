@@ -0,0 +1,66 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::{fluet, run};
+
+#[test]
+fn let_destructures_lists_and_objects() {
+    let output = run("let_patterns", r#"
+        let [x, y] = [1, 2];
+        let [head, ...tail] = [3, 4, 5];
+        let [a, [b, c = 'default']] = [6, [7]];
+        let {kind, message: text} = error('boom');
+        let [first = 'none', ...rest] = [];
+        print([x, y, head, tail, a, b, c, kind, text, first, rest]);
+    "#);
+
+    assert_eq!(output, "[1, 2, 3, [4, 5], 6, 7, 'default', 'Error', 'boom', 'none', []]\n");
+}
+
+#[test]
+fn parameters_and_for_heads_destructure() {
+    let output = run("param_patterns", r#"
+        fn swap([a, b]) { [b, a] }
+        fn describe({message}, [first, ...others] = ['nobody']) { print([message, first, others]); }
+
+        print(swap([1, 2]));
+        describe(error('list'), ['a', 'b', 'c']);
+        describe(error('empty'));
+
+        for [key, value] in [['a', 1], ['b', 2]] {
+            print([key, value]);
+        }
+    "#);
+
+    assert_eq!(output, "[2, 1]\n['list', 'a', ['b', 'c']]\n['empty', 'nobody', []]\n['a', 1]\n['b', 2]\n");
+}
+
+#[test]
+fn missing_fields_are_errors() {
+    let output = fluet("missing_field", "\nlet {missing} = error('boom');\n", &[]);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("has no property 'missing'") && stderr.contains("missing_field.fl:2:"), "{stderr}");
+}
+
+#[test]
+fn missing_fields_take_their_defaults() {
+    let output = run("missing_field_default", r#"
+        class Options {
+            fn init(a) { this.a = a; }
+        }
+
+        let {a, b = 2} = Options(1);
+        let {kind, message: text = 'none', retries = 3} = error('boom');
+        print(a, b, kind, text, retries);
+    "#);
+
+    assert_eq!(output, "1 2 Error boom 3\n");
+}
@@ -0,0 +1,146 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn documents_a_module_tree_with_links() {
+    let root = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("doc");
+    let (src, out) = (root.join("src"), root.join("out"));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(src.join("shapes")).unwrap();
+
+    fs::write(src.join("shapes").join("circle.fl"), r#"
+        /// Something with an area.
+        interface Shape {
+            fn area();
+        }
+
+        /// A circle, made by [unit].
+        class Circle: Shape {
+            /// Pi r squared.
+            fn area() -> number { 3.14 * this.r * this.r }
+        }
+    "#).unwrap();
+    fs::write(src.join("util.fl"), r#"
+        /// Makes a [Circle] with radius 1.
+        fn unit(scale: number = 1) -> Circle { Circle() }
+    "#).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fluet"))
+        .args(["doc", src.to_str().unwrap(), "--out", out.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.stderr.is_empty(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let index = fs::read_to_string(out.join("index.html")).unwrap();
+    assert!(index.contains("<a href=\"shapes.circle.html\">shapes::circle</a>"));
+    assert!(index.contains("<a href=\"util.html\">util</a>"));
+
+    let circle = fs::read_to_string(out.join("shapes.circle.html")).unwrap();
+    assert!(circle.contains("<section id=\"class.Circle\">"));
+    assert!(circle.contains("<a href=\"shapes.circle.html#interface.Shape\">Shape</a>"));
+    assert!(circle.contains("<a href=\"util.html#fn.unit\">unit</a>"));
+    assert!(circle.contains("id=\"class.Circle.area\""));
+
+    let util = fs::read_to_string(out.join("util.md")).unwrap();
+    assert!(util.contains("### `fn unit(scale: number = …) -> Circle`"));
+    assert!(util.contains("Makes a [Circle](shapes.circle.md#class.Circle) with radius 1."));
+    assert!(fs::read_to_string(out.join("shapes.circle.md")).unwrap()
+        .contains("Implements: [Shape](shapes.circle.md#interface.Shape)"));
+}
+
+#[test]
+fn doc_tests_report_the_line_of_failures() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("doc_tests.fl");
+    fs::write(&path, r#"/// Adds two numbers.
+///
+/// ```
+/// print(add(1, 2));
+/// ```
+///
+/// ```text
+/// not run
+/// ```
+fn add(a, b) { a + b }
+
+/**
+ * ```fl
+ * let x = add(1, 2);
+ * print(x + true);
+ * ```
+ */
+class Broken {}
+
+print("not run either");
+"#).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fluet"))
+        .args(["test", path.to_str().unwrap()])
+        .output()
+        .unwrap();
+    let (stdout, stderr) = (String::from_utf8(output.stdout).unwrap(), String::from_utf8(output.stderr).unwrap());
+
+    assert!(!output.status.success());
+    assert!(stdout.starts_with("3\n"), "{stdout}");
+    assert!(stdout.contains("doc_tests.fl:4 ... ok"));
+    assert!(stdout.contains("doc_tests.fl:14 ... FAILED"));
+    assert!(stdout.contains("1 passed; 1 failed"));
+    assert!(!stdout.contains("not run"));
+    assert!(stderr.contains("doc_tests.fl:15:"), "{stderr}");
+}
+
+#[test]
+fn doc_tests_see_top_level_variables() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("doc_tests_lets.fl");
+    fs::write(&path, r#"let SCALE = 2;
+
+/// ```
+/// assert_eq(double(2), 4);
+/// ```
+fn double(x) { x * SCALE }
+"#).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fluet"))
+        .args(["test", path.to_str().unwrap()])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("1 passed; 0 failed"), "{stdout}");
+}
+
+#[test]
+fn doc_tests_only_run_literal_top_level_variables() {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("doc_tests_side_effects.fl");
+    fs::write(&path, r#"let noisy = print("side effect");
+
+/// ```
+/// assert_eq(LIMIT, 10);
+/// ```
+let LIMIT = 10;
+
+/// ```
+/// assert_eq(LIMIT + 1, 11);
+/// ```
+fn one() { 1 }
+"#).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fluet"))
+        .args(["test", path.to_str().unwrap()])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success(), "{stdout}");
+    assert!(!stdout.contains("side effect"), "{stdout}");
+    assert!(stdout.contains("2 passed; 0 failed"), "{stdout}");
+}
@@ -0,0 +1,241 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::run;
+
+#[test]
+fn counter() {
+    let output = run("counter", r#"
+        fn make_counter() {
+            let count = 0;
+            fn next() {
+                count = count + 1;
+                count
+            }
+            next
+        };
+
+        let a = make_counter();
+        let b = make_counter();
+        print(a(), a(), a());
+        print(b());
+    "#);
+
+    assert_eq!(output, "1 2 3\n1\n");
+}
+
+#[test]
+fn closures_share_captured_variables() {
+    let output = run("shared", r#"
+        fn make() {
+            let value = 0;
+            fn set(v) { value = v; }
+            fn get() { value }
+            (set, get)
+        };
+
+        let (set, get) = make();
+        set(42);
+        print(get());
+    "#);
+
+    assert_eq!(output, "42\n");
+}
+
+#[test]
+fn closures_in_loops() {
+    let output = run("loops", r#"
+        fn collect() {
+            let fns = [];
+            for i in [1, 2, 3] {
+                fn get() { i }
+                fns = [...fns, get];
+            }
+
+            let j = 0;
+            while j < 3 {
+                let k = j * 10;
+                fn get() { k }
+                fns = [...fns, get];
+                j = j + 1;
+            }
+
+            fns
+        };
+
+        for f in collect() {
+            print(f());
+        }
+    "#);
+
+    assert_eq!(output, "1\n2\n3\n0\n10\n20\n");
+}
+
+#[test]
+fn assignment_reaches_enclosing_scopes() {
+    let output = run("assignment", r#"
+        let total = 0;
+        fn add(n) { total = total + n; }
+        add(1);
+        add(2);
+
+        let x = 1;
+        {
+            let y = 2;
+            {
+                x = x + y;
+                y = 0;
+            };
+            print(y);
+        };
+
+        print(total, x);
+    "#);
+
+    assert_eq!(output, "0\n3 3\n");
+}
+
+#[test]
+fn closures_keep_their_resolved_scope() {
+    let output = run("shadowing", r#"
+        let a = "global";
+        {
+            fn show() { print(a); }
+            show();
+            let a = "block";
+            show();
+            print(a);
+        };
+    "#);
+
+    assert_eq!(output, "global\nglobal\nblock\n");
+}
+
+#[test]
+fn captures_pass_through_nested_functions() {
+    let output = run("nested", r#"
+        fn outer() {
+            let a = 1;
+            fn middle() {
+                fn inner() {
+                    a = a + 10;
+                    a
+                }
+                inner
+            }
+
+            let f = middle();
+            f();
+            (f(), a)
+        };
+
+        fn local_recursion() {
+            fn fact(n) { if n == 0 then 1 else n * fact(n - 1) }
+            fact(5)
+        };
+
+        print(outer(), local_recursion());
+    "#);
+
+    assert_eq!(output, "(21, 21) 120\n");
+}
+
+#[test]
+fn methods_capture_through_their_class() {
+    let output = run("methods", r#"
+        fn make(greeting) {
+            class Greeter {
+                fn init(name) { this.name = name; }
+                fn greet() {
+                    fn inner() { greeting + ", " + this.name }
+                    inner()
+                }
+                fn louder() { Greeter(this.name + "!") }
+            }
+            Greeter
+        };
+
+        let Greeter = make("hi");
+        print(Greeter("bob").greet(), Greeter("al").louder().greet());
+    "#);
+
+    assert_eq!(output, "hi, bob hi, al!\n");
+}
+
+#[test]
+fn shadowing_in_nested_blocks_keeps_each_slot() {
+    let output = run("shadowing_slots", r#"
+        let _x = 'global';
+        fn f() {
+            let _x = 1;
+            let a = 10;
+            {
+                let _x = 2;
+                let b = 20;
+                {
+                    let _x = 3;
+                    print(_x, a, b);
+                };
+                print(_x, b);
+            };
+            print(_x, a);
+        }
+
+        f();
+        print(_x);
+    "#);
+
+    assert_eq!(output, "3 10 20\n2 20\n1 10\nglobal\n");
+}
+
+#[test]
+fn destructured_names_get_their_own_slots() {
+    let output = run("pattern_slots", r#"
+        class Point {
+            fn init(x, y) { this.x = x; this.y = y; }
+        }
+
+        fn f([a, [b, c], ...rest], {x, y: z}, last) {
+            let [d, e = 'default'] = [a + b];
+            let (g, h) = (c, last);
+            print(a, b, c, rest, x, z, d, e, g, h);
+        }
+
+        f([1, [2, 3], 4, 5], Point(6, 7), 8);
+    "#);
+
+    assert_eq!(output, "1 2 3 [4, 5] 6 7 3 default 3 8\n");
+}
+
+#[test]
+fn catch_variables_get_their_own_slots() {
+    let output = run("catch_slots", r#"
+        fn f() {
+            let before = 'before';
+            try {
+                let inside = 'inside';
+                throw inside;
+            } catch e {
+                let after = 'after';
+                print(before, e, after);
+            }
+
+            try {
+                throw 'again';
+            } catch e {
+                print(e, before);
+            }
+        }
+
+        f();
+    "#);
+
+    assert_eq!(output, "before inside after\nagain before\n");
+}
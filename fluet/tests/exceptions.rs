@@ -0,0 +1,139 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::{fluet, run};
+
+#[test]
+fn thrown_values_are_caught() {
+    let output = run("try_catch", r#"
+        fn fail(value) { throw value; }
+
+        try {
+            fail('message');
+            print('not run');
+        } catch e {
+            print(e);
+        }
+
+        try {
+            throw error('custom');
+        } catch e {
+            print(e.kind);
+            print(e.message);
+            print(e.line);
+        }
+    "#);
+
+    assert_eq!(output, "message\nError\ncustom\n12\n");
+}
+
+#[test]
+fn runtime_errors_are_caught_as_error_values() {
+    let output = run("runtime_errors", r#"
+        fn one(a) { a }
+
+        try {
+            one(1, 2);
+        } catch e {
+            print(e.kind);
+            print(e.line);
+        }
+    "#);
+
+    assert_eq!(output, "RuntimeError\n5\n");
+}
+
+#[test]
+fn finally_always_runs() {
+    let output = run("finally", r#"
+        fn early() {
+            try {
+                return 'returned';
+            } finally {
+                print('finally after return');
+            }
+        }
+
+        try {
+            print('body');
+        } finally {
+            print('finally after body');
+        }
+
+        try {
+            try {
+                throw 'inner';
+            } finally {
+                print('finally after throw');
+            }
+        } catch e {
+            print(e);
+        }
+
+        print(early());
+    "#);
+
+    assert_eq!(
+        output,
+        "body\nfinally after body\nfinally after throw\ninner\nfinally after return\nreturned\n"
+    );
+}
+
+#[test]
+fn returning_from_finally_replaces_the_pending_completion() {
+    let output = run("finally_return", r#"
+        fn thrown() {
+            try {
+                throw 'lost';
+            } finally {
+                return 'from finally';
+            }
+        }
+
+        fn one(a) { a }
+
+        fn failed() {
+            try {
+                one(1, 2);
+            } catch e {
+                throw e;
+            } finally {
+                return 'recovered';
+            }
+        }
+
+        fn returned() {
+            try {
+                return 'try';
+            } finally {
+                return 'finally';
+            }
+        }
+
+        print(thrown());
+        print(failed());
+        print(returned());
+    "#);
+
+    assert_eq!(output, "from finally\nrecovered\nfinally\n");
+}
+
+#[test]
+fn uncaught_throws_stop_the_program() {
+    let output = fluet("uncaught", r#"
+        print('before');
+        throw 'oops';
+        print('after');
+    "#, &[]);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "before\n");
+    assert!(stderr.contains("Exception: uncaught 'oops'") && stderr.contains("uncaught.fl:3:"), "{stderr}");
+}
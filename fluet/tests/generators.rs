@@ -0,0 +1,106 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::{fluet, run};
+
+#[test]
+fn generators_drive_for_loops_and_spreads() {
+    let output = run("generators", r#"
+        fn range(start, end) {
+            if start < end then {
+                yield start;
+                for i in range(start + 1, end) { yield i; }
+            } else {};
+        }
+
+        for i in range(0, 3) { print(i); }
+        print([...range(3, 6)]);
+        print([...range(1, 1)]);
+    "#);
+
+    assert_eq!(output, "0\n1\n2\n[3, 4, 5]\n[]\n");
+}
+
+#[test]
+fn errors_thrown_from_generators_propagate() {
+    let output = run("generator_throws", r#"
+        fn failing() {
+            yield 1;
+            throw 'stopped';
+        }
+
+        try {
+            for x in failing() { print(x); }
+        } catch e {
+            print('caught', e);
+        }
+    "#);
+
+    assert_eq!(output, "1\ncaught stopped\n");
+
+    let output = fluet("generator_errors", r#"
+        fn failing() {
+            yield 1;
+            throw 'uncaught';
+        }
+
+        print([...failing()]);
+        print('not run');
+    "#, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.stdout.is_empty());
+    assert!(stderr.contains("uncaught") && stderr.contains(":4:"), "{stderr}");
+}
+
+#[test]
+fn many_generators_can_be_alive() {
+    let output = run("many_generators", r#"
+        fn once(x) { yield x; }
+
+        let generators = map();
+        let i = 0;
+        while i < 50000 {
+            insert(generators, i, once(i));
+            i = i + 1;
+        }
+
+        print(len(generators), [...generators[49999]]);
+    "#);
+
+    assert_eq!(output, "50000 [49999]\n");
+}
+
+#[test]
+fn generators_have_room_to_recurse() {
+    let output = run("generator_recursion", r#"
+        fn sum(n) { if n == 0 then 0 else n + sum(n - 1) }
+        fn sums() { yield sum(200); }
+
+        print([...sums()]);
+    "#);
+
+    assert_eq!(output, "[20100]\n");
+}
+
+#[test]
+fn stack_overflows_in_generators_are_errors() {
+    let output = run("generator_overflow", r#"
+        fn forever(n) { 1 + forever(n + 1) }
+        fn overflowing() { yield forever(0); }
+
+        try {
+            print([...overflowing()]);
+        } catch e {
+            print(e.kind, e.message);
+        }
+    "#);
+
+    assert_eq!(output, "RuntimeError Stack overflow: too many nested calls.\n");
+}
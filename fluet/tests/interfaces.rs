@@ -0,0 +1,83 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::{fluet, run};
+
+#[test]
+fn implements_checks_declared_interfaces() {
+    let output = run("implements", r#"
+        interface Plugin {
+            fn name();
+            fn run(input);
+        }
+
+        class Shout: Plugin {
+            fn name() { 'shout' }
+            fn run(input) { input + '!' }
+        }
+
+        class Other {
+            fn name() { 'other' }
+            fn run(input) { input }
+        }
+
+        for plugin in [Shout(), Other()] {
+            if implements(plugin, Plugin) then print(plugin.run('hi')) else print(plugin.name());
+        }
+    "#);
+
+    assert_eq!(output, "hi!\nother\n");
+}
+
+#[test]
+fn classes_are_checked_when_defined() {
+    let output = fluet("nonconforming", r#"
+        interface Plugin { fn run(input); }
+        print('defining');
+        class Broken: Plugin { fn run() {} }
+        print('defined');
+    "#, &[]);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "defining\n");
+    assert!(stderr.contains("Method 'run' of class Broken takes 0 arguments"), "{stderr}");
+}
+
+#[test]
+fn methods_only_have_to_be_callable_like_the_interface() {
+    let output = run("interface_arity", r#"
+        interface Plugin {
+            fn run(input);
+            fn log(first, ...more);
+        }
+
+        class Defaults: Plugin {
+            fn run(input, verbose = false) { if verbose then 'loud ' + input else input }
+            fn log(...all) { all }
+        }
+
+        class Rest: Plugin {
+            fn run(...inputs) { inputs }
+            fn log(first = 0, ...more) { (first, more) }
+        }
+
+        print(Defaults().run('a'), Defaults().log(1, 2), Rest().run('b'), Rest().log(1, 2));
+    "#);
+
+    assert_eq!(output, "a [1, 2] ['b'] (1, [2])\n");
+
+    let output = fluet("interface_arity_mismatch", r#"
+        interface Plugin { fn log(first, ...more); }
+        class Fixed: Plugin { fn log(first, second = 2) {} }
+    "#, &[]);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Method 'log' of class Fixed takes 2 arguments"), "{stderr}");
+}
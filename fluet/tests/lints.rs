@@ -0,0 +1,56 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::fluet;
+
+const SOURCE: &str = r#"
+    fn f(a, b, _c) {
+        let unused = 1;
+        let x = 2;
+        if true then print(x) else null;
+        {
+            let x = 3;
+            print(x);
+        };
+        while (false) { }
+        print(0 / 0 == 1);
+        return a;
+        print("never");
+    }
+
+    print(f(1, 2, 3));
+"#;
+
+fn warnings(args: &[&str]) -> String {
+    let output = fluet("lints", SOURCE, args);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2\n3\nfalse\n1\n");
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn lints_warn() {
+    let stderr = warnings(&[]);
+    assert!(stderr.contains("Unused variable unused."), "{stderr}");
+    assert!(stderr.contains("Unused parameter b."), "{stderr}");
+    assert!(stderr.contains("Code after this return is unreachable."), "{stderr}");
+    assert!(stderr.contains("x shadows a variable in an enclosing scope."), "{stderr}");
+    assert!(stderr.contains("This condition is always true."), "{stderr}");
+    assert!(stderr.contains("This condition is always false."), "{stderr}");
+    assert!(stderr.contains("Comparing with NaN is always false."), "{stderr}");
+    assert_eq!(stderr.matches("warning").count(), 7, "{stderr}");
+}
+
+#[test]
+fn lints_can_be_allowed() {
+    let stderr = warnings(&["--allow", "unused_variable", "-A", "constant_condition"]);
+    assert!(!stderr.contains("Unused"), "{stderr}");
+    assert!(!stderr.contains("This condition"), "{stderr}");
+    assert_eq!(stderr.matches("warning").count(), 3, "{stderr}");
+}
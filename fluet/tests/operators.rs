@@ -0,0 +1,60 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::{fluet, run};
+
+#[test]
+fn classes_overload_operators() {
+    let output = run("operators", r#"
+        class Vec {
+            fn init(x, y) { this.x = x; this.y = y; }
+            fn __add(other) { Vec(this.x + other.x, this.y + other.y) }
+            fn __mul(k) { Vec(this.x * k, this.y * k) }
+            fn __neg() { Vec(-this.x, -this.y) }
+            fn __eq(other) { this.x == other.x and this.y == other.y }
+            fn __lt(other) { this.x < other.x }
+            fn __index(i) { if i == 0 then this.x else this.y }
+            fn __call(k) { this.x * k + this.y }
+            fn show() { '(' + this.x + ', ' + this.y + ')' }
+        }
+
+        let v = Vec(1, 2) + Vec(3, 4);
+        print(v.show(), (v * 2).show(), (-v).show());
+        print(v == Vec(4, 6), v != Vec(4, 6), v == Vec(0, 0));
+        print(Vec(1, 0) < Vec(2, 0), v[0], v[1], v(10));
+    "#);
+
+    assert_eq!(output, "(4, 6) (8, 12) (-4, -6)\ntrue false false\ntrue 4 6 46\n");
+}
+
+#[test]
+fn either_side_can_overload_equality() {
+    let output = run("right_eq", r#"
+        class Anything {
+            fn __eq(_other) { true }
+        }
+
+        print(1 == Anything(), 'x' != Anything(), Anything() == null);
+        print((1, Anything()) == (1, 2), (Anything(), 2) == (1, 3), (1,) == (1, 2));
+    "#);
+
+    assert_eq!(output, "true false true\ntrue false false\n");
+}
+
+#[test]
+fn missing_operator_methods_are_errors() {
+    let output = fluet("no_operator", r#"
+        class Plain {}
+        print(Plain() + 1);
+    "#, &[]);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("invalid binary operation '+'") && stderr.contains("no_operator.fl:3:"), "{stderr}");
+}
@@ -0,0 +1,77 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::{fluet, run};
+
+#[test]
+fn null_coalescing() {
+    let output = run("coalescing", r#"
+        fn loud() { print('evaluated'); 2 }
+        print(null ?? 1);
+        print(0 ?? loud());
+        print(false ?? 3);
+        print(null ?? null ?? 'last');
+    "#);
+
+    assert_eq!(output, "1\n0\nfalse\nlast\n");
+}
+
+#[test]
+fn optional_access_and_calls_skip_null() {
+    let output = run("optional_access", r#"
+        fn one() { 1 }
+        let missing = null;
+        let f = one;
+        print(missing?.name);
+        print(missing?.method());
+        print(missing?.());
+        print(f?.());
+    "#);
+
+    assert_eq!(output, "null\nnull\nnull\n1\n");
+}
+
+#[test]
+fn plain_access_on_null_is_an_error() {
+    let output = fluet("null_property", "let missing = null;\nprint(missing.name);\n", &[]);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("null has no property 'name'") && stderr.contains(":2:"), "{stderr}");
+}
+
+#[test]
+fn optional_chains_short_circuit() {
+    let output = run("optional_chains", r#"
+        class Node {
+            fn init(next) { this.next = next; this.items = [next]; }
+            fn get() { this.next }
+        }
+
+        let missing = null;
+        let node = Node(Node(null));
+        print(missing?.next, missing?.next.next.next, missing?.get().next);
+        print(missing?.items[0].next, missing?.(1).next);
+        print(node?.next.next, node?.get()?.get()?.get());
+        print(missing?.next ?? 'default');
+    "#);
+
+    assert_eq!(output, "null null null\nnull null\nnull null\ndefault\n");
+}
+
+#[test]
+fn grouping_ends_an_optional_chain() {
+    let output = fluet("grouped_chain", r#"
+        let missing = null;
+        print((missing?.next).next);
+    "#, &[]);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("null has no property 'next'"), "{stderr}");
+}
@@ -0,0 +1,28 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::run;
+
+#[test]
+fn pipelines_insert_the_first_argument() {
+    let output = run("pipeline", r#"
+        fn double(x) { x * 2 }
+        fn sub(a, b) { a - b }
+        fn label(value, prefix = 'value', suffix = '') { prefix + ': ' + value + suffix }
+
+        print(5 |> double);
+        print(5 |> double |> sub(1));
+        print(10 |> sub(3) |> label(suffix: '!'));
+        print(1 + 2 |> double);
+        print(true or false |> label);
+    "#);
+
+    assert_eq!(output, "10\n9\nvalue: 7!\n6\nvalue: true\n");
+}
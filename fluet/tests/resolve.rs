@@ -0,0 +1,76 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::{fluet, run};
+
+#[test]
+fn semantic_errors_are_reported_together_before_running() {
+    let output = fluet("semantic_errors", r#"
+        print("not run");
+        fn f(x, x) {
+            let y = 1;
+            let y = 2;
+            missing(y)
+        }
+        class K {
+            fn m() {}
+            fn m() {}
+        }
+        return 1;
+    "#, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(output.stdout.is_empty());
+    for error in [
+        "x is already declared in this scope.",
+        "y is already declared in this scope.",
+        "missing is not defined.",
+        "Method m is already declared in K.",
+        "Cannot return from top-level code.",
+    ] {
+        assert!(stderr.contains(error), "{error} not in {stderr}");
+    }
+}
+
+#[test]
+fn globals_can_be_used_before_their_declaration() {
+    let output = run("forward_globals", r#"
+        fn even(n) { if n == 0 then true else odd(n - 1) }
+        fn odd(n) { if n == 0 then false else even(n - 1) }
+        fn show() { print(even(limit)); }
+
+        let limit = 4;
+        show();
+
+        // Redeclaring globals is allowed, like in the REPL
+        let limit = 3;
+        show();
+    "#);
+
+    assert_eq!(output, "true\nfalse\n");
+}
+
+#[test]
+fn top_level_code_cannot_use_globals_before_their_declaration() {
+    let output = fluet("top_level_forward_globals", r#"
+        print("not run");
+        print(x);
+        greet();
+        x = 2;
+
+        let x = 1;
+        fn greet() { print(x); }
+    "#, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(output.stdout.is_empty());
+    assert_eq!(stderr.matches("x is used before it is declared.").count(), 2, "{stderr}");
+    assert!(stderr.contains("greet is used before it is declared."), "{stderr}");
+}
@@ -0,0 +1,70 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::run;
+
+#[test]
+fn tail_recursion_runs_in_constant_stack() {
+    let output = run("tail_recursion", r#"
+        fn sum(n, acc) { if n == 0 then acc else sum(n - 1, acc + n) }
+        print(sum(100000, 0));
+
+        fn count(n) {
+            if n == 0 then { return 'done'; } else null;
+            return count(n - 1);
+        }
+        print(count(100000));
+    "#);
+
+    assert_eq!(output, "5000050000\ndone\n");
+}
+
+#[test]
+fn mutual_tail_recursion() {
+    let output = run("mutual_tail_recursion", r#"
+        fn even(n) { if n == 0 then true else odd(n - 1) }
+        fn odd(n) { if n == 0 then false else even(n - 1) }
+        print(even(100001));
+    "#);
+
+    assert_eq!(output, "false\n");
+}
+
+#[test]
+fn calls_in_try_are_not_tail_calls() {
+    let output = run("calls_in_try", r#"
+        fn fail() { throw error('boom'); }
+        fn guarded() {
+            try {
+                return fail();
+            } catch e {
+                return 'caught';
+            }
+        }
+        print(guarded());
+    "#);
+
+    assert_eq!(output, "caught\n");
+}
+
+#[test]
+fn calls_at_the_end_of_blocks_are_tail_calls() {
+    let output = run("block_tail_calls", r#"
+        fn loop_to(n) { if n == 0 then { 'done' } else { loop_to(n - 1) } }
+        print(loop_to(100000));
+
+        fn early(flag) {
+            if flag then { return 'early'; } else { 'late' }
+        }
+        print(early(true), early(false));
+    "#);
+
+    assert_eq!(output, "done\nearly late\n");
+}
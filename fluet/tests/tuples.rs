@@ -0,0 +1,51 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::{fluet, run};
+
+#[test]
+fn tuples_compare_structurally() {
+    let output = run("tuple_equality", r#"
+        fn min_max(a, b) { if a < b then (a, b) else (b, a) }
+        let pair = min_max(3, 1);
+        print(pair, pair.0, pair.1, (1) == 1, (1,) == (1,));
+        print(pair == (1, 3), pair == (3, 1), (1, (2, 'x')) == (1, (2, 'x')));
+        print([1] == [1], (0 / 0,) == (0 / 0,));
+
+        let (low, high) = pair;
+        print(low, high);
+    "#);
+
+    assert_eq!(output, "(1, 3) 1 3 true true\ntrue false true\nfalse false\n1 3\n");
+}
+
+#[test]
+fn tuples_are_map_keys() {
+    let output = run("tuple_keys", r#"
+        let grid = map();
+        insert(grid, (0, 0), 'origin');
+        insert(grid, (1, 2), 'a');
+        insert(grid, (1, 2), 'b');
+        insert(grid, 0 / 0, 'nan');
+        insert(grid, -0, 'zero');
+
+        print(grid[(0, 0)], grid[(1, 2)], grid[(2, 1)], grid[0 / 0], grid[0]);
+        print(len(grid), grid);
+    "#);
+
+    assert_eq!(output, "origin b null nan zero\n4 {(0, 0): 'origin', (1, 2): 'b', NaN: 'nan', -0: 'zero'}\n");
+}
+
+#[test]
+fn tuple_fields_must_exist() {
+    let output = fluet("tuple_fields", "print((1, 2).2);", &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("(1, 2) has no field 2"), "{stderr}");
+}
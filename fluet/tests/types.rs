@@ -0,0 +1,125 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::{fluet, run};
+
+#[test]
+fn annotated_code_runs() {
+    let output = run("annotated", r#"
+        class Point {
+            fn init(x: number, y: number) {
+                this.x = x;
+                this.y = y;
+            }
+        }
+
+        fn add(a: number, b: number) -> number { a + b }
+        fn origin() -> Point { Point(0, 0) }
+
+        let sum: number = add(1, 2);
+        let p: Point = origin();
+        let xs: [number] = [sum, p.x];
+        let f: fn(number, number) -> number = add;
+        print(sum, xs, f(p.y, 1));
+    "#);
+
+    assert_eq!(output, "3 [3, 0] 1\n");
+}
+
+#[test]
+fn mismatches_are_reported_before_running() {
+    let source = r#"
+        fn greet(name: string) -> string { 'hello ' + name }
+        print('started');
+        greet(42);
+    "#;
+
+    let output = fluet("mismatch", source, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.stdout.is_empty());
+    assert!(stderr.contains("Expected string but found number."), "{stderr}");
+
+    let output = fluet("mismatch", source, &["--no-typecheck"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "started\n");
+}
+
+#[test]
+fn returns_in_branches_end_the_body() {
+    let output = run("branch_returns", r#"
+        fn sign(n) -> number {
+            if n < 0 then {
+                return -1;
+            } else {
+                return 1;
+            };
+        }
+
+        print(sign(-5), sign(5));
+    "#);
+
+    assert_eq!(output, "-1 1\n");
+}
+
+#[test]
+fn paths_that_fall_through_are_checked() {
+    let output = fluet("fall_through", r#"
+        fn sign(n) -> number {
+            if n < 0 then {
+                return -1;
+            } else null;
+        }
+    "#, &[]);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Expected number but found null."), "{stderr}");
+}
+
+#[test]
+fn inferred_mismatches_only_warn() {
+    let output = fluet("inferred_mismatches", r#"
+        try {
+            1 - 'a';
+        } catch e {
+            print('caught');
+        }
+
+        try {
+            print(-true);
+        } catch e {
+            print('caught again');
+        }
+    "#, &[]);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "caught\ncaught again\n", "{stderr}");
+    assert!(stderr.contains("Cannot apply '-' to number and string."), "{stderr}");
+    assert!(stderr.contains("Unary minus operator can only be applied to numbers, not bool."), "{stderr}");
+}
+
+#[test]
+fn captured_variables_can_be_reassigned() {
+    let output = run("captured_reassigned", r#"
+        fn greet(name: string) -> string { 'hi ' + name }
+        let who = 0;
+        fn say() { print(greet(who)); }
+        who = 'bob';
+        say();
+    "#);
+
+    assert_eq!(output, "hi bob\n");
+
+    let output = fluet("captured_constant", r#"
+        fn greet(name: string) -> string { 'hi ' + name }
+        let who = 0;
+        fn say() { print(greet(who)); }
+    "#, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Expected string but found number."), "{stderr}");
+}
@@ -0,0 +1,56 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+use common::{fluet, run};
+
+#[test]
+fn rest_parameters_collect_extra_arguments() {
+    let output = run("rest_params", r#"
+        fn log(level, ...parts) { print(level, parts, len(parts)); }
+        log('info');
+        log('warn', 1, 2, 3);
+        print('print', 'takes', 'any', 'number');
+    "#);
+
+    assert_eq!(output, "info [] 0\nwarn [1, 2, 3] 3\nprint takes any number\n");
+}
+
+#[test]
+fn spreading_arguments_and_list_elements() {
+    let output = run("spread", r#"
+        fn add(a, b, c) { a + b + c }
+        let xs = [1, 2];
+        print(add(...xs, 3), add(0, ...xs));
+        print([0, ...xs, ...[4, 5], 6], [...[]]);
+
+        fn collect(...all) { all }
+        print(collect(...xs, ...xs));
+    "#);
+
+    assert_eq!(output, "6 3\n[0, 1, 2, 4, 5, 6] []\n[1, 2, 1, 2]\n");
+}
+
+#[test]
+fn spreading_checks_arity_and_types() {
+    let output = fluet("spread_errors", r#"
+        fn pair(a, b) { [a, b] }
+        pair(...[1, 2, 3]);
+    "#, &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Expected at most 2 arguments but got 3."), "{stderr}");
+
+    let output = fluet("spread_type", "print(...1);", &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Cannot spread 1"), "{stderr}");
+
+    let output = fluet("len_location", "\nprint(len(1));", &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("1 has no length") && stderr.contains(":2:"), "{stderr}");
+}
@@ -0,0 +1,94 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use common::stmt::Stmt;
+use criterion::{criterion_group, criterion_main, Criterion};
+use interpreter::Interpreter;
+use lexer::Lexer;
+use parser::Parser;
+use resolver::Resolver;
+
+fn parse(source: &str) -> Vec<Stmt> {
+    let mut lexer = Lexer::new(source.to_string(), "<bench>".to_string());
+    let tokens = lexer.scan_tokens();
+    Parser::new(tokens.to_vec()).parse().unwrap()
+}
+
+fn bench_script(c: &mut Criterion, name: &str, source: &str) {
+    let statements = parse(source);
+
+    c.bench_function(name, |b| b.iter(|| {
+        let mut interpreter = Interpreter::new();
+        Resolver::new().resolve(&statements, &mut interpreter).unwrap();
+        interpreter.interpret(statements.clone()).unwrap();
+    }));
+}
+
+fn locals(c: &mut Criterion) {
+    bench_script(c, "while loop", r#"
+        fn sum(n) {
+            let total = 0;
+            let i = 0;
+            while i < n {
+                total = total + i;
+                i = i + 1;
+            }
+            total
+        };
+        sum(10000);
+    "#);
+
+    bench_script(c, "nested scopes", r#"
+        fn run(n) {
+            let a = 1;
+            let b = 2;
+            let i = 0;
+            while i < n {
+                let c = a + b;
+                {
+                    let d = c * 2;
+                    a = d - c;
+                };
+                i = i + 1;
+            }
+            a
+        };
+        run(5000);
+    "#);
+
+    bench_script(c, "closure counter", r#"
+        fn make_counter() {
+            let count = 0;
+            fn next() {
+                count = count + 1;
+                count
+            }
+            next
+        };
+
+        fn run(n) {
+            let next = make_counter();
+            let i = 0;
+            while i < n {
+                next();
+                i = i + 1;
+            }
+        };
+        run(5000);
+    "#);
+
+    bench_script(c, "recursive fib", r#"
+        fn fib(n) {
+            if n < 2 then n else fib(n - 1) + fib(n - 2)
+        };
+        fib(15);
+    "#);
+}
+
+criterion_group!(benches, locals);
+criterion_main!(benches);
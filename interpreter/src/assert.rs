@@ -0,0 +1,111 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use common::errors::{ReportKind, Result};
+use common::expr::Arg;
+use common::location::Location;
+
+use crate::value::callable::NativeFn;
+use crate::value::Value;
+use crate::Interpreter;
+
+
+/// The check made by `assert`, `assert_eq` or `assert_ne`.
+#[derive(Clone, Copy)]
+pub(crate) enum Assertion {
+    Eq,
+    Ne,
+    True,
+}
+
+impl Assertion {
+    /// The assertion a value makes when called, if it is one of the assert
+    /// functions.
+    pub(crate) fn of(value: &Value) -> Option<Assertion> {
+        match value {
+            Value::NativeFn(f, _) if std::ptr::fn_addr_eq(*f, assert as NativeFn) => Some(Assertion::True),
+            Value::NativeFn(f, _) if std::ptr::fn_addr_eq(*f, assert_eq as NativeFn) => Some(Assertion::Eq),
+            Value::NativeFn(f, _) if std::ptr::fn_addr_eq(*f, assert_ne as NativeFn) => Some(Assertion::Ne),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn arity(&self) -> usize {
+        match self {
+            Assertion::Eq | Assertion::Ne => 2,
+            Assertion::True => 1,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Assertion::Eq => "assert_eq",
+            Assertion::Ne => "assert_ne",
+            Assertion::True => "assert",
+        }
+    }
+
+    /// Makes the assertion. When the expressions of the arguments are known,
+    /// a failure shows their source next to the values they evaluated to.
+    pub(crate) fn check(
+        &self,
+        interpreter: &mut Interpreter,
+        values: &[Value],
+        args: Option<&[Arg]>,
+        location: &Location,
+    ) -> Result<Value> {
+        let source = |i: usize| args.map(|args| format!("`{}`", args[i].expr()));
+
+        let message = match self {
+            Assertion::True => {
+                if interpreter.is_truthy_restrictive(&values[0], location)? {
+                    return Ok(Value::Null);
+                }
+
+                match source(0) {
+                    Some(source) => format!("assertion failed: {source}"),
+                    None => "assertion failed".to_string(),
+                }
+            },
+            Assertion::Eq | Assertion::Ne => {
+                let equal = interpreter.is_equal(values[0].clone(), values[1].clone(), location)?;
+                if equal == matches!(self, Assertion::Eq) {
+                    return Ok(Value::Null);
+                }
+
+                let relation = match self {
+                    Assertion::Eq => "is not equal to",
+                    _ => "is equal to",
+                };
+                let failure = match (source(0), source(1)) {
+                    (Some(left), Some(right)) => format!("{} failed: {left} {relation} {right}", self.name()),
+                    _ => format!("{} failed", self.name()),
+                };
+
+                format!("{failure}\n  left: {}\n right: {}", values[0], values[1])
+            },
+        };
+
+        error!(ReportKind::AssertionError, &message, location)
+    }
+}
+
+// The functions scripts call. Calls written out in the source are checked by
+// the interpreter instead, which knows the argument expressions.
+
+pub(crate) fn assert(interpreter: &mut Interpreter, args: Vec<Value>, location: &Location) -> Result<Value> {
+    Assertion::True.check(interpreter, &args, None, location)
+}
+
+pub(crate) fn assert_eq(interpreter: &mut Interpreter, args: Vec<Value>, location: &Location) -> Result<Value> {
+    Assertion::Eq.check(interpreter, &args, None, location)
+}
+
+pub(crate) fn assert_ne(interpreter: &mut Interpreter, args: Vec<Value>, location: &Location) -> Result<Value> {
+    Assertion::Ne.check(interpreter, &args, None, location)
+}
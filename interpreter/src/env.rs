@@ -15,10 +15,32 @@ use common::{error, token::Token};
 
 use crate::value::Value;
 
-#[derive(Clone, Debug)]
+/// A local variable, which closures that capture it share with the scope it
+/// is declared in.
+pub type Cell = Rc<RefCell<Value>>;
+
+/// The variables a closure captured, in the order the resolver numbered them.
+pub type Upvalues = Rc<[Cell]>;
+
+/// Where the resolver found a local variable, relative to the code using it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Local {
+    /// A variable of the running function, `depth` scopes up from the
+    /// innermost one, in the given slot.
+    Slot(usize, usize),
+    /// A variable of an enclosing function, by its index in the upvalues.
+    Upvalue(usize),
+}
+
+#[derive(Debug, Default)]
 pub struct Env {
     parent: Option<Rc<RefCell<Env>>>,
+    /// Variables in the global scope, which are looked up by name.
     pub values: HashMap<String, Value>,
+    /// Variables in a local scope, in the slots the resolver gave them.
+    slots: Vec<Cell>,
+    /// The variables captured by the function this scope is part of.
+    upvalues: Upvalues,
 }
 
 impl Env {
@@ -26,40 +48,83 @@ impl Env {
         Self {
             parent: None,
             values: HashMap::new(),
+            slots: Vec::new(),
+            upvalues: Rc::new([]),
         }
     }
 
+    /// Creates a scope nested in `parent`, as part of the same function.
     pub fn from_parent(parent: Rc<RefCell<Env>>) -> Self {
+        let upvalues = parent.borrow().upvalues.clone();
+        Self::with_upvalues(parent, upvalues)
+    }
+
+    /// Creates the outermost scope of a function or class body. Its parent
+    /// should be the global scope, as everything else it uses is captured.
+    pub fn with_upvalues(parent: Rc<RefCell<Env>>, upvalues: Upvalues) -> Self {
         Self {
             parent: Some(parent),
             values: HashMap::new(),
+            slots: Vec::new(),
+            upvalues,
         }
     }
 
-    pub fn get_at(&self, distance: usize, name: &Token) -> Option<Value> {
-        self.ancestor(distance).borrow().values.get(name.lexeme()).map(|val| val.clone())
+    /// Reads a local variable, as found by the resolver.
+    pub fn get(&self, local: Local) -> Option<Value> {
+        match local {
+            Local::Slot(depth, slot) => self.get_at(depth, slot),
+            Local::Upvalue(index) => self.upvalues.get(index).map(|cell| cell.borrow().clone()),
+        }
     }
 
-    fn ancestor(&self, distance: usize) -> Rc<RefCell<Env>> {
-        if distance == 0 {
-            return Rc::new(RefCell::new(self.clone()));
+    /// Reads a local variable from the scope `depth` levels up.
+    pub fn get_at(&self, depth: usize, slot: usize) -> Option<Value> {
+        if depth == 0 {
+            return self.slots.get(slot).map(|cell| cell.borrow().clone());
         }
 
-        match &self.parent {
-            Some(parent) => parent.borrow().ancestor(distance - 1),
-            None => unreachable!("ancestor called with non-zero distance but no parent"),
+        self.enclosing().borrow().get_at(depth - 1, slot)
+    }
+
+    /// The cell of a local variable, for a closure to capture.
+    pub fn capture(&self, local: Local) -> Cell {
+        match local {
+            Local::Slot(0, slot) => self.slots[slot].clone(),
+            Local::Slot(depth, slot) => self.enclosing().borrow().capture(Local::Slot(depth - 1, slot)),
+            Local::Upvalue(index) => self.upvalues[index].clone(),
         }
     }
 
-    pub fn define(&mut self, name: String, value: Value) {
-        self.values.insert(name, value);
+    /// The cells of the variables a closure captures.
+    pub fn capture_all(&self, locals: &[Local]) -> Upvalues {
+        locals.iter().map(|&local| self.capture(local)).collect()
     }
 
-    pub fn assign_at(&mut self, distance: usize, name: &Token, value: Value) {
-        self.ancestor(distance).borrow_mut().values.insert(
-            name.lexeme().to_string(),
-            value
-        );
+    /// Defines a variable in this scope. Local variables are defined in the
+    /// same order the resolver declared them, which makes that their slot.
+    pub fn define(&mut self, name: &str, value: Value) {
+        if self.parent.is_some() {
+            self.slots.push(Rc::new(RefCell::new(value)));
+        } else {
+            self.values.insert(name.to_string(), value);
+        }
+    }
+
+    /// Sets the variable defined last in this scope. Functions and classes
+    /// are defined before they are created, so that they can capture
+    /// themselves.
+    pub fn set_last(&mut self, name: &str, value: Value) {
+        match self.slots.last() {
+            Some(cell) if self.parent.is_some() => *cell.borrow_mut() = value,
+            _ => self.define(name, value),
+        }
+    }
+
+    /// Assigns to a local variable. Closures share the cells of the variables
+    /// they capture with the code that created them, so both see the change.
+    pub fn assign_local(&self, local: Local, value: Value) {
+        *self.capture(local).borrow_mut() = value;
     }
 
     pub fn assign(&mut self, name: &Token, value: &Value) -> Result<()> {
@@ -79,4 +144,8 @@ impl Env {
     pub fn parent(&self) -> Option<Rc<RefCell<Env>>> {
         self.parent.clone()
     }
+
+    fn enclosing(&self) -> &Rc<RefCell<Env>> {
+        self.parent.as_ref().expect("resolved depth is deeper than the scope chain")
+    }
 }
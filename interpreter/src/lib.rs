@@ -11,66 +11,261 @@ extern crate common;
 
 pub mod value;
 pub mod env;
+pub mod scheduler;
+mod assert;
 
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::mem;
+use std::ptr;
 use std::rc::Rc;
 
-use common::errors::{ReportKind, Result, report_error};
-use common::expr::Expr;
+use common::errors::{FluetError, ReportKind, Result, report_error};
+use common::expr::{Arg, Expr};
 use common::location::Location;
-use common::stmt::Stmt;
+use common::pattern::Pattern;
+use common::stmt::{Function, Stmt};
 use common::token::{Literal, Token, TokenType};
+use corosensei::Yielder;
 
-use env::Env;
+use assert::Assertion;
+use env::{Env, Local, Upvalues};
 use value::Value;
-use value::callable::Callable;
+use value::callable::{accepts, function_arity, params_arity, Arity, Callable};
+use scheduler::{Fiber, Scheduler};
+use value::channel::Channel;
+use value::class::{Class, Instance};
+use value::error::ErrorValue;
+use value::generator::{Generator, Resumer};
+use value::interface::Interface;
+use value::map::Map;
 
 pub struct Interpreter {
     env: Rc<RefCell<Env>>,
-    globals: Env,
-    locals: HashMap<usize, usize>,
+    globals: Rc<RefCell<Env>>,
+    /// Where each local variable is, by expression id.
+    locals: Rc<HashMap<usize, Local>>,
+    /// The variables each function and class captures, by id.
+    captures: Rc<HashMap<usize, Vec<Local>>>,
     return_value: Option<Value>,
+    thrown: Option<Value>,
+    /// Set while running the body of a generator, which is suspended by `yield`.
+    yielder: Option<*const Yielder<Resumer, Value>>,
+    scheduler: Rc<Scheduler>,
+    /// Set while running a fiber, which is suspended when it blocks. A
+    /// generator's body gets the fiber resuming it each time it's resumed.
+    fiber: Resumer,
+    /// Set while running on the stack of a generator or fiber, which calls
+    /// must not grow past.
+    stack_limit: Option<usize>,
+    /// Whether calls in tail position can be deferred to the caller, which is
+    /// only the case while running the body of a function.
+    tail_calls: bool,
+    tail_call: Option<TailCall>,
+}
+
+/// A call whose callee and arguments have been evaluated. Calls in tail
+/// position are made by the function call they replace.
+pub(crate) struct TailCall {
+    pub callee: Value,
+    pub args: Vec<Value>,
+    pub named_args: Vec<(Token, Value)>,
+    pub location: Location,
 }
 
 impl Interpreter {
     pub fn new() -> Self {
+        let globals = {
+            let mut globals = Env::new();
+
+            globals.define("print", Value::NativeFn(|_, args, _| {
+                println!("{}", display_args(&args));
+                Ok(Value::Null)
+            }, Arity::Variadic(0)));
+
+            globals.define("eprint", Value::NativeFn(|_, args, _| {
+                eprintln!("{}", display_args(&args));
+                Ok(Value::Null)
+            }, Arity::Variadic(0)));
+
+            globals.define("error", Value::NativeFn(|_, args, _| {
+                let message = match &args[0] {
+                    Value::String(s) => s.clone(),
+                    value => value.to_string(),
+                };
+
+                Ok(Value::Error(Rc::new(ErrorValue::new("Error", message))))
+            }, Arity::Fixed(1)));
+
+            globals.define("len", Value::NativeFn(|_, args, location| {
+                match &args[0] {
+                    Value::List(list) => Ok(Value::Number(list.borrow().len() as f64)),
+                    Value::Map(map) => Ok(Value::Number(map.borrow().len() as f64)),
+                    Value::String(s) => Ok(Value::Number(s.chars().count() as f64)),
+                    value => error!(
+                        ReportKind::TypeError,
+                        &format!("{} has no length", value),
+                        location
+                    ),
+                }
+            }, Arity::Fixed(1)));
+
+            globals.define("map", Value::NativeFn(|_, _, _| {
+                Ok(Value::Map(Rc::new(RefCell::new(Map::new()))))
+            }, Arity::Fixed(0)));
+
+            globals.define("insert", Value::NativeFn(|_, mut args, location| {
+                let value = args.pop().unwrap();
+                let key = args.pop().unwrap();
+                match &args[0] {
+                    Value::Map(map) => map.borrow_mut().insert(key, value),
+                    value => return error!(
+                        ReportKind::TypeError,
+                        &format!("{} is not a map", value),
+                        location
+                    ),
+                }
+
+                Ok(Value::Null)
+            }, Arity::Fixed(3)));
+
+            globals.define("chan", Value::NativeFn(|_, _, _| {
+                Ok(Value::Channel(Rc::new(Channel::new())))
+            }, Arity::Fixed(0)));
+
+            globals.define("send", Value::NativeFn(|interpreter, mut args, location| {
+                let value = args.pop().unwrap();
+                match &args[0] {
+                    Value::Channel(channel) => channel.send(interpreter, value, location)?,
+                    value => return error!(
+                        ReportKind::TypeError,
+                        &format!("{} is not a channel", value),
+                        location
+                    ),
+                }
+
+                Ok(Value::Null)
+            }, Arity::Fixed(2)));
+
+            globals.define("recv", Value::NativeFn(|interpreter, args, location| {
+                match &args[0] {
+                    Value::Channel(channel) => channel.recv(interpreter, location),
+                    value => error!(
+                        ReportKind::TypeError,
+                        &format!("{} is not a channel", value),
+                        location
+                    ),
+                }
+            }, Arity::Fixed(1)));
+
+            globals.define("implements", Value::NativeFn(|_, args, location| {
+                match (&args[0], &args[1]) {
+                    (Value::Instance(instance), Value::Interface(interface)) => {
+                        Ok(Value::Bool(instance.class.implements(interface)))
+                    },
+                    (_, Value::Interface(_)) => Ok(Value::Bool(false)),
+                    (_, value) => error!(
+                        ReportKind::TypeError,
+                        &format!("{} is not an interface", value),
+                        location
+                    ),
+                }
+            }, Arity::Fixed(2)));
+
+            globals.define("assert", Value::NativeFn(assert::assert, Arity::Fixed(1)));
+            globals.define("assert_eq", Value::NativeFn(assert::assert_eq, Arity::Fixed(2)));
+            globals.define("assert_ne", Value::NativeFn(assert::assert_ne, Arity::Fixed(2)));
+
+            globals.define("__env", Value::NativeFn(|interpreter, _, _| {
+                Ok(Value::String(format!("{:#?}", interpreter.env.borrow())))
+            }, Arity::Fixed(0)));
+
+            globals.define("__locals", Value::NativeFn(|interpreter, _, _| {
+                Ok(Value::String(format!("{:#?}", interpreter.locals)))
+            }, Arity::Fixed(0)));
+
+            Rc::new(RefCell::new(globals))
+        };
+
         Self {
-            env: Rc::new(RefCell::new(Env::new())),
-            globals: {
-                let mut globals = Env::new();
-    
-                globals.define("print".to_string(), Value::NativeFn(|_, args| {
-                    match &args[0] {
-                        Value::String(s) => println!("{}", s),
-                        value => println!("{}", value),
-                    }
-    
-                    Ok(Value::Null)
-                }, 1));
-    
-                globals.define("eprint".to_string(), Value::NativeFn(|_, args| {
-                    match &args[0] {
-                        Value::String(s) => eprintln!("{}", s),
-                        value => eprintln!("{}", value),
-                    }
-    
-                    Ok(Value::Null)
-                }, 1));
+            // Top-level code runs directly in the global scope
+            env: globals.clone(),
+            globals,
+            locals: Rc::new(HashMap::new()),
+            captures: Rc::new(HashMap::new()),
+            return_value: None,
+            thrown: None,
+            yielder: None,
+            scheduler: Rc::new(Scheduler::new()),
+            fiber: None,
+            stack_limit: None,
+            tail_calls: false,
+            tail_call: None,
+        }
+    }
 
-                globals.define("__env".to_string(), Value::NativeFn(|interpreter, _| {
-                    Ok(Value::String(format!("{:#?}", interpreter.env.borrow())))
-                }, 0));
+    /// Creates an interpreter that runs in `env` and shares this one's globals,
+    /// resolved locals and captures.
+    pub fn fork(&self, env: Rc<RefCell<Env>>) -> Self {
+        Self {
+            env,
+            globals: self.globals.clone(),
+            locals: self.locals.clone(),
+            captures: self.captures.clone(),
+            return_value: None,
+            thrown: None,
+            yielder: None,
+            scheduler: self.scheduler.clone(),
+            fiber: None,
+            stack_limit: None,
+            tail_calls: false,
+            tail_call: None,
+        }
+    }
+
+    /// Runs every spawned fiber to completion.
+    pub fn run_fibers(&mut self) -> Result<()> {
+        Scheduler::run_until(self, None, Scheduler::is_idle)
+    }
+
+    /// Blocks the current fiber until `ready` returns `true`. Outside of a
+    /// fiber, other fibers are run until it does, and a deadlock is reported
+    /// at `location`.
+    pub(crate) fn block_until(&mut self, ready: impl Fn() -> bool, location: &Location) -> Result<()> {
+        match self.fiber {
+            Some(fiber) => {
+                while !ready() {
+                    // SAFETY: The fiber's yielder is only set on the
+                    // interpreter that runs it, from inside its coroutine, and
+                    // outlives that interpreter. Generators only use it while
+                    // the fiber is resuming them, on top of its stack.
+                    unsafe { (*fiber).suspend(()) };
+                }
 
-                globals.define("__locals".to_string(), Value::NativeFn(|interpreter, _| {
-                    Ok(Value::String(format!("{:#?}", interpreter.locals)))
-                }, 0));
-    
-                globals
+                Ok(())
             },
-            locals: HashMap::new(),
-            return_value: None,
+            None => Scheduler::run_until(self, Some(location), |_| ready()),
+        }
+    }
+
+    /// Fails if a call at `location` could overflow the stack of the
+    /// generator or fiber it's made on.
+    pub(crate) fn check_stack(&self, location: &Location) -> Result<()> {
+        let Some(limit) = self.stack_limit else {
+            return Ok(());
+        };
+
+        // The stack grows down, so this is about how much of it is used
+        let top = 0u8;
+        if (ptr::addr_of!(top) as usize) < limit {
+            return error!(
+                ReportKind::RuntimeError,
+                "Stack overflow: too many nested calls.",
+                location
+            );
         }
+
+        Ok(())
     }
 
     pub fn with_env<R>(&mut self, env: Rc<RefCell<Env>>, closure: impl FnOnce(&mut Interpreter) -> Result<R>) -> Result<R> {
@@ -81,9 +276,34 @@ impl Interpreter {
         value
     }
 
-    pub fn extend_locals(&mut self, locals: HashMap<usize, usize>) {
-        println!("extend locals: {locals:#?}");
-        self.locals.extend(locals);
+    /// The names defined in the global scope, including native functions.
+    pub fn global_names(&self) -> Vec<String> {
+        self.globals.borrow().values.keys().cloned().collect()
+    }
+
+    pub fn extend_locals(&mut self, locals: HashMap<usize, Local>) {
+        Rc::make_mut(&mut self.locals).extend(locals);
+    }
+
+    pub fn extend_captures(&mut self, captures: HashMap<usize, Vec<Local>>) {
+        Rc::make_mut(&mut self.captures).extend(captures);
+    }
+
+    /// Takes the cells of the variables the function or class with this id
+    /// uses from the scopes around it.
+    fn capture(&self, id: usize) -> Upvalues {
+        match self.captures.get(&id) {
+            Some(captures) => self.env.borrow().capture_all(captures),
+            None => Rc::new([]),
+        }
+    }
+
+    /// Defines a function or class in the current scope. It is declared before
+    /// it is created, so that it can capture itself to refer to its own name.
+    fn define_declaration(&mut self, name: &Token, create: impl FnOnce(&mut Interpreter) -> Value) {
+        self.env.borrow_mut().define(name.lexeme(), Value::Null);
+        let value = create(self);
+        self.env.borrow_mut().set_last(name.lexeme(), value);
     }
 
     pub fn interpret(&mut self, statements: Vec<Stmt>) -> Result<()> {
@@ -91,7 +311,7 @@ impl Interpreter {
             self.execute(&statement)?;
         }
 
-        Ok(())
+        self.run_fibers()
     }
 
     pub fn execute(&mut self, statement: &Stmt) -> Result<()> {
@@ -100,65 +320,375 @@ impl Interpreter {
         }
 
         match statement {
+            Stmt::Class(id, name, interfaces, methods, ..) => {
+                let methods: HashMap<_, _> = methods
+                    .iter()
+                    .map(|method| (method.name.lexeme().to_string(), method.clone()))
+                    .collect();
+
+                let interfaces = interfaces
+                    .iter()
+                    .map(|interface| self.implemented_interface(name, &methods, interface))
+                    .collect::<Result<_>>()?;
+
+                let captures = methods
+                    .values()
+                    .filter_map(|method| Some((method.id, self.captures.get(&method.id)?.clone())))
+                    .collect();
+
+                self.define_declaration(name, |interpreter| {
+                    let env = Env::with_upvalues(interpreter.globals.clone(), interpreter.capture(*id));
+                    let class = Class::new(
+                        name.lexeme().to_string(),
+                        methods,
+                        interfaces,
+                        Rc::new(RefCell::new(env)),
+                        captures
+                    );
+                    Value::Class(Rc::new(class))
+                });
+                Ok(())
+            },
             Stmt::Expr(expr) => {
                 self.evaluate(expr)?;
                 Ok(())
             },
-            Stmt::Fn(name, _, _, _) => {
-                self.env.borrow_mut().define(
-                    name.lexeme().to_string(),
-                    Value::Fn(statement.clone(), self.env.clone())
-                );
+            Stmt::Fn(function) => {
+                self.define_declaration(&function.name, |interpreter| {
+                    Value::Fn(function.clone(), interpreter.capture(function.id))
+                });
                 Ok(())
             },
-            Stmt::Let(name, expr) => {
-                let value = self.evaluate(expr)?;
-                self.env.borrow_mut().define(name.lexeme().to_string(), value);
+            Stmt::For(pattern, iterable, body) => self.execute_for(pattern, iterable, body),
+            Stmt::Interface(name, methods, _) => {
+                let methods = methods
+                    .iter()
+                    .map(|method| (method.name.lexeme().to_string(), params_arity(&method.params)))
+                    .collect();
+                let interface = Interface::new(name.lexeme().to_string(), methods);
+
+                self.env.borrow_mut().define(name.lexeme(), Value::Interface(Rc::new(interface)));
                 Ok(())
             },
+            Stmt::Let(pattern, _, expr, _) => {
+                let value = self.evaluate(expr)?;
+                self.bind_pattern(pattern, value)
+            },
             Stmt::Loop(body) => self.execute_loop(body),
-            Stmt::Return(expr) => {
-                self.return_value = Some(self.evaluate(expr)?);
+            Stmt::Return(_, expr) => {
+                self.return_value = Some(self.evaluate_tail(expr)?);
+                Ok(())
+            },
+            Stmt::Throw(keyword, expr) => {
+                let value = self.evaluate(expr)?;
+                self.throw(value, keyword.location())
+            },
+            Stmt::Try(body, catch, finally) => {
+                // The try statement has to see what the call does, so calls in
+                // it can't be deferred
+                let tail_calls = mem::replace(&mut self.tail_calls, false);
+                let result = self.execute_try(body, catch, finally);
+                self.tail_calls = tail_calls;
+                result
+            },
+            Stmt::While(keyword, condition, body) => self.execute_while(keyword, condition, body),
+            Stmt::Yield(keyword, expr) => {
+                let value = self.evaluate(expr)?;
+                match self.yielder {
+                    // SAFETY: The yielder is only set on the interpreter that
+                    // runs a generator's body, from inside its coroutine, and
+                    // outlives that interpreter.
+                    Some(yielder) => unsafe {
+                        self.fiber = (*yielder).suspend(value);
+                    },
+                    None => return error!(
+                        ReportKind::RuntimeError,
+                        "'yield' outside of a generator.",
+                        keyword.location()
+                    ),
+                }
+
                 Ok(())
             },
-            Stmt::While(condition, body) => self.execute_while(condition, body),
         }
     }
 
-    fn execute_loop(&mut self, body: &Vec<Stmt>) -> Result<()> {
-        loop {
-            self.env = Rc::new(RefCell::new(Env::from_parent(self.env.clone())));
+    /// Evaluates an interface that a class declares it implements, and checks
+    /// that the class has every method it requires.
+    fn implemented_interface(
+        &mut self,
+        class: &Token,
+        methods: &HashMap<String, Rc<Function>>,
+        interface: &Expr,
+    ) -> Result<Rc<Interface>> {
+        let location = match interface {
+            Expr::Variable(_, name) => name.location(),
+            _ => class.location(),
+        };
+
+        let interface = match self.evaluate(interface)? {
+            Value::Interface(interface) => interface,
+            value => return error!(
+                ReportKind::TypeError,
+                &format!("{value} is not an interface."),
+                location
+            ),
+        };
 
-            for statement in body {
-                self.execute(statement)?;
+        for (name, arity) in &interface.methods {
+            match methods.get(name) {
+                None => return error!(
+                    ReportKind::TypeError,
+                    &format!(
+                        "Class {} is missing method '{name}' of interface {}.",
+                        class.lexeme(),
+                        interface.name
+                    ),
+                    location
+                ),
+                Some(method) if !accepts(&method.params, *arity) => return error!(
+                    ReportKind::TypeError,
+                    &format!(
+                        "Method '{name}' of class {} takes {} but interface {} requires {arity}.",
+                        class.lexeme(),
+                        function_arity(method),
+                        interface.name
+                    ),
+                    method.name.location()
+                ),
+                Some(_) => {},
             }
+        }
+
+        Ok(interface)
+    }
+
+    /// Runs a function's body in the current environment, which should
+    /// already have the arguments bound.
+    pub(crate) fn call_function(&mut self, function: &Function) -> Result<Value> {
+        for statement in &function.body {
+            self.execute(statement)?;
+        }
+
+        if let Some(value) = self.return_value.take() {
+            return Ok(value);
+        }
+
+        // The trailing expression can return from a block in it
+        let value = self.evaluate_tail(&function.ret)?;
+        Ok(self.return_value.take().unwrap_or(value))
+    }
+
+    fn execute_scoped(&mut self, statements: &[Stmt], env: Env) -> Result<()> {
+        self.with_env(Rc::new(RefCell::new(env)), |interpreter| {
+            for statement in statements {
+                interpreter.execute(statement)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn execute_try(
+        &mut self,
+        body: &[Stmt],
+        catch: &Option<(Token, Vec<Stmt>)>,
+        finally: &Option<Vec<Stmt>>,
+    ) -> Result<()> {
+        let mut result = self.execute_scoped(body, Env::from_parent(self.env.clone()));
+
+        if let (Err(err), Some((name, handler))) = (&result, catch) {
+            let error = self.error_value(err);
+            let mut env = Env::from_parent(self.env.clone());
+            env.define(name.lexeme(), error);
+            result = self.execute_scoped(handler, env);
+        }
+
+        if let Some(finally) = finally {
+            // The finally block has to run even if the try or catch block
+            // returned or threw, so set those aside until it's done.
+            let return_value = self.return_value.take();
+            let thrown = self.thrown.take();
+            self.execute_scoped(finally, Env::from_parent(self.env.clone()))?;
+
+            // Returning from the finally block replaces what the try or catch
+            // block did, even an error
+            if self.return_value.is_some() {
+                return Ok(());
+            }
+            self.return_value = return_value;
+            self.thrown = thrown;
+        }
+
+        result
+    }
+
+    /// Unwinds to the closest enclosing `catch` block, or reports the value as
+    /// an uncaught exception if there is none.
+    fn throw(&mut self, value: Value, location: &Location) -> Result<()> {
+        let value = match value {
+            Value::Error(error) if error.location.row == 0 => Value::Error(Rc::new(ErrorValue {
+                location: location.clone(),
+                ..(*error).clone()
+            })),
+            value => value,
+        };
+        let location = match &value {
+            Value::Error(error) => error.location.clone(),
+            _ => location.clone(),
+        };
+
+        let message = format!("uncaught {}", value);
+        self.thrown = Some(value);
+        error!(ReportKind::Exception, &message, &location)
+    }
+
+    /// Converts an error that unwound to a `catch` block back into the value
+    /// visible to the script.
+    fn error_value(&mut self, err: &FluetError) -> Value {
+        match (err.kind, self.thrown.take()) {
+            (ReportKind::Exception, Some(value)) => value,
+            _ => Value::Error(Rc::new(ErrorValue::from(err))),
+        }
+    }
+
+    /// Defines the names bound by a pattern in the current environment.
+    pub fn bind_pattern(&mut self, pattern: &Pattern, value: Value) -> Result<()> {
+        match pattern {
+            Pattern::List(bracket, elements, rest) => {
+                let values = match &value {
+                    Value::List(list) => list.borrow().clone(),
+                    Value::Tuple(tuple) => tuple.to_vec(),
+                    value => return error!(
+                        ReportKind::TypeError,
+                        &format!("Cannot destructure {} as a sequence", value),
+                        bracket.location()
+                    ),
+                };
+
+                for (i, (pattern, default)) in elements.iter().enumerate() {
+                    let value = values.get(i).cloned().unwrap_or(Value::Null);
+                    let value = self.or_default(value, default)?;
+                    self.bind_pattern(pattern, value)?;
+                }
+
+                if let Some(rest) = rest {
+                    let rest_values = values.get(elements.len()..).unwrap_or_default().to_vec();
+                    self.env.borrow_mut().define(
+                        rest.lexeme(),
+                        Value::List(Rc::new(RefCell::new(rest_values)))
+                    );
+                }
+
+                Ok(())
+            },
+            Pattern::Name(name) => {
+                self.env.borrow_mut().define(name.lexeme(), value);
+                Ok(())
+            },
+            Pattern::Object(_, fields) => {
+                for (key, pattern, default) in fields {
+                    // A missing field takes the default, like a null one does
+                    let field = match self.property(&value, key) {
+                        Some(field) => field,
+                        None if default.is_some() => Value::Null,
+                        None => self.get_property(value.clone(), key)?,
+                    };
+                    let field = self.or_default(field, default)?;
+                    self.bind_pattern(pattern, field)?;
+                }
+
+                Ok(())
+            },
+        }
+    }
+
+    fn or_default(&mut self, value: Value, default: &Option<Expr>) -> Result<Value> {
+        match (value, default) {
+            (Value::Null, Some(default)) => self.evaluate(default),
+            (value, _) => Ok(value),
+        }
+    }
+
+    fn execute_for(&mut self, pattern: &Pattern, iterable: &Expr, body: &[Stmt]) -> Result<()> {
+        let location = pattern.token().location();
+        let iterable = match self.evaluate(iterable)? {
+            Value::Tuple(tuple) => Value::List(Rc::new(RefCell::new(tuple.to_vec()))),
+            value @ (Value::List(_) | Value::Generator(_)) => value,
+            value => return error!(
+                ReportKind::TypeError,
+                &format!("Cannot iterate over {}", value),
+                location
+            ),
+        };
+
+        let mut i = 0;
+        loop {
+            let element = match &iterable {
+                Value::List(list) => list.borrow().get(i).cloned(),
+                Value::Generator(generator) => self.resume_generator(generator, location)?,
+                _ => unreachable!(),
+            };
+            let Some(element) = element else {
+                break;
+            };
+            i += 1;
+
+            let env = Rc::new(RefCell::new(Env::from_parent(self.env.clone())));
+            self.with_env(env, |interpreter| {
+                interpreter.bind_pattern(pattern, element)?;
+                for statement in body {
+                    interpreter.execute(statement)?;
+                }
+
+                Ok(())
+            })?;
 
-            self.env = self.env.clone().borrow().parent().unwrap();
+            if self.return_value.is_some() {
+                break;
+            }
         }
 
         Ok(())
     }
 
-    fn execute_while(&mut self, condition: &Expr, body: &Vec<Stmt>) -> Result<()> {
+    fn resume_generator(
+        &mut self,
+        generator: &Rc<RefCell<Generator>>,
+        location: &Location,
+    ) -> Result<Option<Value>> {
+        match generator.try_borrow_mut() {
+            Ok(mut generator) => generator.resume(self),
+            Err(_) => error!(
+                ReportKind::RuntimeError,
+                "Generator is already running.",
+                location
+            ),
+        }
+    }
+
+    fn execute_loop(&mut self, body: &[Stmt]) -> Result<()> {
+        loop {
+            // Each iteration gets a fresh scope, so closures created in the
+            // body capture that iteration's variables.
+            self.execute_scoped(body, Env::from_parent(self.env.clone()))?;
+
+            if self.return_value.is_some() {
+                return Ok(());
+            }
+        }
+    }
+
+    fn execute_while(&mut self, keyword: &Token, condition: &Expr, body: &[Stmt]) -> Result<()> {
         let mut condition_value = self.evaluate(condition)?;
 
-        // FIXME: implement reading expression locations somehow
-        while self.is_truthy_restrictive(
-            &condition_value,
-            &Location {
-                filename: "".to_string(),
-                line: "".to_string(),
-                row: 0,
-                column: 0,
-            },
-        )? {
-            self.env = Rc::new(RefCell::new(Env::from_parent(self.env.clone())));
+        while self.is_truthy_restrictive(&condition_value, keyword.location())? {
+            self.execute_scoped(body, Env::from_parent(self.env.clone()))?;
 
-            for statement in body {
-                self.execute(statement)?;
+            if self.return_value.is_some() {
+                break;
             }
 
-            self.env = self.env.clone().borrow().parent().unwrap();
             condition_value = self.evaluate(condition)?;
         }
 
@@ -169,25 +699,63 @@ impl Interpreter {
         match expr {
             Expr::Assignment(expr_id, name, value) => {
                 let value = self.evaluate(value)?;
-                if let Some(distance) = self.locals.get(expr_id) {
-                    self.env.borrow_mut().assign_at(*distance, name, value.clone());
+                if let Some(&local) = self.locals.get(expr_id) {
+                    self.env.borrow().assign_local(local, value.clone());
                 } else {
-                    self.globals.assign(name, &value)?;
+                    self.globals.borrow_mut().assign(name, &value)?;
                 }
 
                 Ok(value)
             },
             Expr::Binary(lhs, op, rhs) => self.evaluate_binary(lhs, op, rhs),
-            Expr::Block(statements, expr)
+            Expr::Block(_, statements, expr, _)
                 => self.evaluate_block(statements, expr, true),
-            Expr::Call(callee, paren, args)
-                => self.evaluate_call(callee, paren, args),
+            Expr::Call(..) | Expr::Get(..) | Expr::Index(..) => {
+                Ok(self.evaluate_chain(expr)?.unwrap_or(Value::Null))
+            },
             Expr::Grouping(expr) => self.evaluate(expr),
-            Expr::If(condition, then_branch, else_branch) => {
-                self.evaluate_conditional(condition, then_branch, else_branch)
+            Expr::If(keyword, condition, then_branch, else_branch) => {
+                self.evaluate_conditional(keyword, condition, then_branch, else_branch, false)
+            },
+            Expr::List(elements) => {
+                let elements = self.evaluate_spreadable(elements)?;
+                Ok(Value::List(Rc::new(RefCell::new(elements))))
             },
             Expr::Literal(literal) => Ok(self.evaluate_literal(literal)),
             Expr::Logical(lhs, op, rhs) => self.evaluate_logical(lhs, op, rhs),
+            Expr::This(expr_id, keyword) => self.lookup_variable(keyword, expr_id),
+            Expr::Tuple(elements) => {
+                let elements = elements
+                    .iter()
+                    .map(|element| self.evaluate(element))
+                    .collect::<Result<Vec<Value>>>()?;
+                Ok(Value::Tuple(Rc::new(elements)))
+            },
+            Expr::Set(object, name, value) => {
+                let object = self.evaluate(object)?;
+                let Value::Instance(instance) = object else {
+                    return error!(
+                        ReportKind::TypeError,
+                        &format!("Cannot set property '{}' on {}", name.lexeme(), object),
+                        name.location()
+                    );
+                };
+
+                let value = self.evaluate(value)?;
+                instance.set(name.lexeme(), value.clone());
+                Ok(value)
+            },
+            Expr::Spawn(keyword, function) => {
+                let callee = Value::Fn(function.clone(), self.capture(function.id));
+                let fiber = Fiber::new(self.fork(self.globals.clone()), callee, keyword.location())?;
+                self.scheduler.spawn(fiber);
+                Ok(Value::Null)
+            },
+            Expr::Spread(dots, _) => error!(
+                ReportKind::SyntaxError,
+                "Spreading is only allowed in lists and argument lists",
+                dots.location()
+            ),
             Expr::Unary(op, expr) => self.evaluate_unary(op, expr),
             Expr::Variable(expr_id, name) => self.lookup_variable(name, expr_id),
         }
@@ -197,9 +765,19 @@ impl Interpreter {
         let lhs = self.evaluate(lhs)?;
         let rhs = self.evaluate(rhs)?;
 
+        if let (Value::Instance(instance), Some(method)) = (&lhs, operator_method(op.token_type())) {
+            if let Some(method) = Instance::bind(instance, method) {
+                return method.call(self, vec![rhs], vec![], op.location());
+            }
+        }
+
         match (lhs, op.token_type(), rhs) {
-            (lhs, TokenType::BangEqual, rhs) => Ok(Value::Bool(!self.is_equal(lhs, rhs))),
-            (lhs, TokenType::EqualEqual, rhs) => Ok(Value::Bool(self.is_equal(lhs, rhs))),
+            (lhs, TokenType::BangEqual, rhs) => {
+                Ok(Value::Bool(!self.is_equal(lhs, rhs, op.location())?))
+            }
+            (lhs, TokenType::EqualEqual, rhs) => {
+                Ok(Value::Bool(self.is_equal(lhs, rhs, op.location())?))
+            }
 
             (Value::Number(lhs), TokenType::Greater, Value::Number(rhs)) => {
                 Ok(Value::Bool(lhs > rhs))
@@ -248,58 +826,255 @@ impl Interpreter {
 
     pub fn evaluate_block(
         &mut self,
-        statements: &Vec<Stmt>,
+        statements: &[Stmt],
         expr: &Expr,
         create_environment: bool) -> Result<Value>
     {
         if create_environment {
-            self.env = Rc::new(RefCell::new(Env::from_parent(self.env.clone())));
+            let env = Rc::new(RefCell::new(Env::from_parent(self.env.clone())));
+            return self.with_env(env, |interpreter| {
+                interpreter.evaluate_block(statements, expr, false)
+            });
         }
 
         for statement in statements {
             self.execute(statement)?;
         }
 
-        let expr = self.evaluate(expr)?;
-        if create_environment {
-            self.env = self.env.clone().borrow().parent().unwrap();
+        match self.return_value {
+            Some(_) => Ok(Value::Null),
+            None => self.evaluate(expr),
+        }
+    }
+
+    /// Evaluates a call, property access or index, which can be part of an
+    /// optional chain like `a?.b.c()`. Returns `None` if a `?.` in the chain
+    /// found null, which makes the whole chain null.
+    fn evaluate_chain(&mut self, expr: &Expr) -> Result<Option<Value>> {
+        match expr {
+            Expr::Call(callee, paren, args) => match self.prepare_call(callee, paren, args)? {
+                Some(call) => self.call_prepared(call, args).map(Some),
+                None => Ok(None),
+            },
+            Expr::Get(object, op, name) => self.evaluate_get(object, op, name),
+            Expr::Index(object, bracket, index) => self.evaluate_index(object, bracket, index),
+            expr => self.evaluate(expr).map(Some),
         }
+    }
 
-        Ok(expr)
+    /// Makes a call returned by `prepare_call`. Assertions also get the
+    /// expressions of their arguments, to show them when they fail.
+    fn call_prepared(&mut self, call: TailCall, args: &[Arg]) -> Result<Value> {
+        match Assertion::of(&call.callee) {
+            Some(assertion) if call.named_args.is_empty() && call.args.len() == assertion.arity() => {
+                // Spread arguments don't line up with the values
+                let spread = args.iter().any(|arg| matches!(arg.expr(), Expr::Spread(..)));
+                let args = (!spread && args.len() == call.args.len()).then_some(args);
+                assertion.check(self, &call.args, args, &call.location)
+            },
+            _ => call.callee.call(self, call.args, call.named_args, &call.location),
+        }
     }
 
-    fn evaluate_call(&mut self, callee: &Expr, paren: &Token, args: &Vec<Expr>) -> Result<Value> {
-        let callee = self.evaluate(callee)?;
+    /// Evaluates an expression in tail position. Calls to functions there are
+    /// left in `tail_call` for the caller to make once the current call has
+    /// returned, so tail recursion doesn't grow the stack.
+    fn evaluate_tail(&mut self, expr: &Expr) -> Result<Value> {
+        if !self.tail_calls {
+            return self.evaluate(expr);
+        }
 
-        let args = args
-            .iter()
-            .map(|expr| self.evaluate(expr))
-            .collect::<Result<Vec<Value>>>()?;
+        match expr {
+            Expr::Call(callee, paren, args) => match self.prepare_call(callee, paren, args)? {
+                Some(call) if matches!(&call.callee, Value::Fn(function, _) if !function.generator) => {
+                    self.tail_call = Some(call);
+                    Ok(Value::Null)
+                },
+                Some(call) => self.call_prepared(call, args),
+                None => Ok(Value::Null),
+            },
+            Expr::Block(_, statements, expr, _) => {
+                let env = Rc::new(RefCell::new(Env::from_parent(self.env.clone())));
+                self.with_env(env, |interpreter| {
+                    for statement in statements {
+                        interpreter.execute(statement)?;
+                    }
 
-        callee.call(self, args, paren.location())
+                    match interpreter.return_value {
+                        Some(_) => Ok(Value::Null),
+                        None => interpreter.evaluate_tail(expr),
+                    }
+                })
+            },
+            Expr::Grouping(expr) => self.evaluate_tail(expr),
+            Expr::If(keyword, condition, then_branch, else_branch) => {
+                self.evaluate_conditional(keyword, condition, then_branch, else_branch, true)
+            },
+            expr => self.evaluate(expr),
+        }
+    }
+
+    /// Evaluates the callee and arguments of a call without making it. Returns
+    /// `None` if an optional call short-circuits.
+    fn prepare_call(
+        &mut self,
+        callee: &Expr,
+        paren: &Token,
+        args: &[Arg],
+    ) -> Result<Option<TailCall>> {
+        // `a?.method()` skips the call as well when `a` is null
+        let Some(callee) = self.evaluate_chain(callee)? else {
+            return Ok(None);
+        };
+
+        if paren.token_type() == TokenType::QuestionDot && matches!(callee, Value::Null) {
+            return Ok(None);
+        }
+
+        let mut positional_args = vec![];
+        let mut named_args = vec![];
+        for arg in args {
+            match arg {
+                Arg::Named(name, expr) => named_args.push((name.clone(), self.evaluate(expr)?)),
+                Arg::Positional(expr) => {
+                    positional_args.extend(self.evaluate_spreadable(std::slice::from_ref(expr))?)
+                },
+            }
+        }
+
+        Ok(Some(TailCall {
+            callee,
+            args: positional_args,
+            named_args,
+            location: paren.location().clone(),
+        }))
+    }
+
+    /// Evaluates the elements of a list literal or argument list, expanding
+    /// spread elements in place.
+    fn evaluate_spreadable(&mut self, exprs: &[Expr]) -> Result<Vec<Value>> {
+        let mut values = vec![];
+        for expr in exprs {
+            match expr {
+                Expr::Spread(dots, expr) => match self.evaluate(expr)? {
+                    Value::List(list) => values.extend(list.borrow().iter().cloned()),
+                    Value::Tuple(tuple) => values.extend(tuple.iter().cloned()),
+                    Value::Generator(generator) => {
+                        while let Some(value) = self.resume_generator(&generator, dots.location())? {
+                            values.push(value);
+                        }
+                    },
+                    value => return error!(
+                        ReportKind::TypeError,
+                        &format!("Cannot spread {}", value),
+                        dots.location()
+                    ),
+                },
+                expr => values.push(self.evaluate(expr)?),
+            }
+        }
+
+        Ok(values)
+    }
+
+    fn evaluate_index(&mut self, object: &Expr, bracket: &Token, index: &Expr) -> Result<Option<Value>> {
+        let Some(object) = self.evaluate_chain(object)? else {
+            return Ok(None);
+        };
+        let index = self.evaluate(index)?;
+
+        if let Value::Instance(instance) = &object {
+            if let Some(method) = Instance::bind(instance, "__index") {
+                return method.call(self, vec![index], vec![], bracket.location()).map(Some);
+            }
+        }
+
+        match (&object, &index) {
+            // Keys that were never inserted give null
+            (Value::Map(map), key) => Ok(Some(map.borrow().get(key).cloned().unwrap_or(Value::Null))),
+            (Value::List(list), Value::Number(number)) => {
+                let list = list.borrow();
+                if number.fract() == 0.0 && *number >= 0.0 && (*number as usize) < list.len() {
+                    Ok(Some(list[*number as usize].clone()))
+                } else {
+                    error!(
+                        ReportKind::RuntimeError,
+                        &format!("Index {} out of bounds for list of length {}", number, list.len()),
+                        bracket.location()
+                    )
+                }
+            },
+            _ => error!(
+                ReportKind::TypeError,
+                &format!("Cannot index {} with {}", object, index),
+                bracket.location()
+            ),
+        }
+    }
+
+    fn evaluate_get(&mut self, object: &Expr, op: &Token, name: &Token) -> Result<Option<Value>> {
+        let Some(object) = self.evaluate_chain(object)? else {
+            return Ok(None);
+        };
+
+        match (object, op.token_type()) {
+            (Value::Null, TokenType::QuestionDot) => Ok(None),
+            (object, _) => self.get_property(object, name).map(Some),
+        }
+    }
+
+    fn get_property(&self, object: Value, name: &Token) -> Result<Value> {
+        match self.property(&object, name) {
+            Some(value) => Ok(value),
+            None if matches!(object, Value::Tuple(_)) && name.token_type() == TokenType::Number => error!(
+                ReportKind::RuntimeError,
+                &format!("{} has no field {}", object, name.lexeme()),
+                name.location()
+            ),
+            None => error!(
+                ReportKind::TypeError,
+                &format!("{} has no property '{}'", object, name.lexeme()),
+                name.location()
+            ),
+        }
+    }
+
+    /// The value of a property, or `None` if the object doesn't have it.
+    fn property(&self, object: &Value, name: &Token) -> Option<Value> {
+        match (object, name.lexeme()) {
+            (Value::Error(error), "kind") => Some(Value::String(error.kind.clone())),
+            (Value::Error(error), "message") => Some(Value::String(error.message.clone())),
+            (Value::Error(error), "file") => Some(Value::String(error.location.filename.clone())),
+            (Value::Error(error), "line") => Some(Value::Number(error.location.row as f64)),
+            (Value::Error(error), "column") => Some(Value::Number(error.location.column as f64)),
+            (Value::Instance(instance), field) => instance.get(field).or_else(|| Instance::bind(instance, field)),
+            (Value::Tuple(tuple), field) if name.token_type() == TokenType::Number => {
+                field.parse::<usize>().ok().and_then(|i| tuple.get(i)).cloned()
+            },
+            _ => None,
+        }
     }
 
     fn evaluate_conditional(
         &mut self,
+        keyword: &Token,
         condition: &Expr,
         then_branch: &Expr,
         else_branch: &Expr,
+        tail: bool,
     ) -> Result<Value> {
         let condition = self.evaluate(condition)?;
 
-        // FIXME: implement reading expression locations somehow
-        if self.is_truthy_restrictive(
-            &condition,
-            &Location {
-                filename: "".to_string(),
-                line: "".to_string(),
-                row: 0,
-                column: 0,
-            },
-        )? {
-            self.evaluate(then_branch)
+        let branch = if self.is_truthy_restrictive(&condition, keyword.location())? {
+            then_branch
+        } else {
+            else_branch
+        };
+
+        if tail {
+            self.evaluate_tail(branch)
         } else {
-            self.evaluate(else_branch)
+            self.evaluate(branch)
         }
     }
 
@@ -329,6 +1104,10 @@ impl Interpreter {
                     self.evaluate(rhs)
                 }
             }
+            TokenType::QuestionQuestion => match lhs {
+                Value::Null => self.evaluate(rhs),
+                lhs => Ok(lhs),
+            },
 
             token_type => error!(
                 ReportKind::TypeError,
@@ -341,6 +1120,12 @@ impl Interpreter {
     fn evaluate_unary(&mut self, op: &Token, expr: &Expr) -> Result<Value> {
         let rhs = self.evaluate(expr)?;
 
+        if let (TokenType::Minus, Value::Instance(instance)) = (op.token_type(), &rhs) {
+            if let Some(method) = Instance::bind(instance, "__neg") {
+                return method.call(self, vec![], vec![], op.location());
+            }
+        }
+
         match op.token_type() {
             TokenType::Minus => match rhs {
                 Value::Number(num) => Ok(Value::Number(-num)),
@@ -360,11 +1145,10 @@ impl Interpreter {
     }
 
     fn lookup_variable(&self, name: &Token, expr_id: &usize) -> Result<Value> {
-        println!("lookup: {:#?}", self.locals);
-        if let Some(distance) = self.locals.get(expr_id) {
-            self.env.borrow().get_at(*distance, name)
+        if let Some(&local) = self.locals.get(expr_id) {
+            self.env.borrow().get(local)
         } else {
-            self.globals.values.get(name.lexeme()).map(|val| val.clone())
+            self.globals.borrow().values.get(name.lexeme()).cloned()
         }.ok_or_else(|| report_error(
             ReportKind::RuntimeError,
             None,
@@ -374,10 +1158,7 @@ impl Interpreter {
     }
 
     fn is_truthy(&self, value: &Value) -> bool {
-        match value {
-            Value::Bool(false) | Value::Null => false,
-            _ => true,
-        }
+        !matches!(value, Value::Bool(false) | Value::Null)
     }
 
     fn is_truthy_restrictive(&self, value: &Value, location: &Location) -> Result<bool> {
@@ -391,13 +1172,65 @@ impl Interpreter {
         }
     }
 
-    fn is_equal(&self, lhs: Value, rhs: Value) -> bool {
-        match (lhs, rhs) {
-            (Value::Number(lhs), Value::Number(rhs)) => lhs == rhs,
-            (Value::String(lhs), Value::String(rhs)) => lhs == rhs,
-            (Value::Bool(lhs), Value::Bool(rhs)) => lhs == rhs,
-            (Value::Null, Value::Null) => true,
-            _ => false,
+    fn is_equal(&mut self, lhs: Value, rhs: Value, location: &Location) -> Result<bool> {
+        // Either side can overload equality, the left one first
+        for (this, other) in [(&lhs, &rhs), (&rhs, &lhs)] {
+            if let Value::Instance(instance) = this {
+                if let Some(method) = Instance::bind(instance, "__eq") {
+                    let result = method.call(self, vec![other.clone()], vec![], location)?;
+                    return Ok(self.is_truthy(&result));
+                }
+            }
+        }
+
+        // Tuples can hold instances that overload it
+        if let (Value::Tuple(lhs), Value::Tuple(rhs)) = (&lhs, &rhs) {
+            if lhs.len() != rhs.len() {
+                return Ok(false);
+            }
+            for (lhs, rhs) in lhs.iter().zip(rhs.iter()) {
+                if !self.is_equal(lhs.clone(), rhs.clone(), location)? {
+                    return Ok(false);
+                }
+            }
+            return Ok(true);
         }
+
+        Ok(lhs == rhs)
+    }
+}
+
+/// The method an instance can define to overload a binary operator. Equality is
+/// overloaded through `__eq` in `is_equal`.
+fn operator_method(operator: TokenType) -> Option<&'static str> {
+    match operator {
+        TokenType::Minus => Some("__sub"),
+        TokenType::Percent => Some("__mod"),
+        TokenType::Plus => Some("__add"),
+        TokenType::Slash => Some("__div"),
+        TokenType::Star => Some("__mul"),
+        TokenType::Greater => Some("__gt"),
+        TokenType::GreaterEqual => Some("__ge"),
+        TokenType::Less => Some("__lt"),
+        TokenType::LessEqual => Some("__le"),
+        _ => None,
+    }
+}
+
+/// Formats arguments the way `print` and `eprint` show them: strings without
+/// quotes, separated by spaces.
+fn display_args(args: &[Value]) -> String {
+    args.iter()
+        .map(|arg| match arg {
+            Value::String(s) => s.clone(),
+            value => value.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
     }
 }
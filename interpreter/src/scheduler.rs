@@ -0,0 +1,134 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use common::errors::{ReportKind, Result};
+use common::location::Location;
+use corosensei::stack::DefaultStack;
+use corosensei::{Coroutine, CoroutineResult, Yielder};
+
+use crate::value::callable::Callable;
+use crate::value::generator::{allocate_stack, stack_limit};
+use crate::{value::Value, Interpreter};
+
+/// A function running concurrently with the rest of the program. Fibers only
+/// give up control when they block on a channel.
+pub struct Fiber {
+    coroutine: Coroutine<(), (), (Result<Value>, Option<Value>), DefaultStack>,
+    /// Where it was spawned.
+    location: Location,
+}
+
+impl Fiber {
+    /// Creates a fiber that calls `callee` with no arguments on `interpreter`.
+    pub(crate) fn new(mut interpreter: Interpreter, callee: Value, location: &Location) -> Result<Self> {
+        let stack = allocate_stack(location)?;
+        interpreter.stack_limit = Some(stack_limit(&stack));
+        let call_location = location.clone();
+
+        let coroutine = Coroutine::with_stack(stack, move |yielder: &Yielder<(), ()>, ()| {
+            interpreter.fiber = Some(yielder);
+            let result = callee.call(&mut interpreter, vec![], vec![], &call_location);
+            (result, interpreter.thrown.take())
+        });
+
+        Ok(Self { coroutine, location: location.clone() })
+    }
+
+    /// Runs the fiber until it blocks. Returns `true` once it has finished.
+    fn resume(&mut self, interpreter: &mut Interpreter) -> Result<bool> {
+        match self.coroutine.resume(()) {
+            CoroutineResult::Yield(()) => Ok(false),
+            CoroutineResult::Return((result, thrown)) => {
+                if thrown.is_some() {
+                    interpreter.thrown = thrown;
+                }
+
+                result.map(|_| true)
+            },
+        }
+    }
+}
+
+/// Runs fibers round-robin, in the order they were spawned, so programs behave
+/// the same way on every run.
+#[derive(Default)]
+pub struct Scheduler {
+    fibers: RefCell<VecDeque<Fiber>>,
+    /// Bumped whenever something happens that could unblock a fiber.
+    progress: Cell<u64>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn(&self, fiber: Fiber) {
+        self.fibers.borrow_mut().push_back(fiber);
+        self.progress();
+    }
+
+    pub fn progress(&self) {
+        self.progress.set(self.progress.get() + 1);
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.fibers.borrow().is_empty()
+    }
+
+    /// Runs fibers until `done` returns `true`. Fails if every fiber is blocked
+    /// without `done` being satisfied, at `blocked` if the caller is waiting
+    /// on something, and where a blocked fiber was spawned otherwise.
+    pub fn run_until(
+        interpreter: &mut Interpreter,
+        blocked: Option<&Location>,
+        done: impl Fn(&Scheduler) -> bool,
+    ) -> Result<()> {
+        let scheduler = interpreter.scheduler.clone();
+
+        // The number of fibers in a row that blocked without making progress
+        let mut stalled = 0;
+        while !done(&scheduler) {
+            let fiber = scheduler.fibers.borrow_mut().pop_front();
+            let Some(mut fiber) = fiber else {
+                return deadlock(blocked.cloned().unwrap_or_default());
+            };
+
+            let progress = scheduler.progress.get();
+            if fiber.resume(interpreter)? {
+                stalled = 0;
+                continue;
+            }
+
+            scheduler.fibers.borrow_mut().push_back(fiber);
+            if scheduler.progress.get() == progress {
+                stalled += 1;
+            } else {
+                stalled = 0;
+            }
+
+            if stalled >= scheduler.fibers.borrow().len() {
+                let spawned = scheduler.fibers.borrow().front().map(|fiber| fiber.location.clone());
+                return deadlock(blocked.cloned().or(spawned).unwrap_or_default());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn deadlock(location: Location) -> Result<()> {
+    error!(
+        ReportKind::RuntimeError,
+        "Deadlock: every fiber is blocked.",
+        &location
+    )
+}
@@ -7,72 +7,324 @@
  */
 
 use std::cell::RefCell;
+use std::fmt;
+use std::mem;
 use std::rc::Rc;
 
-use common::{location::Location, stmt::Stmt};
+use common::{location::Location, pattern::Pattern, stmt::{Function, Param}, token::Token};
 use common::errors::{Result, ReportKind};
 
-use crate::env::Env;
-use crate::{value::Value, Interpreter};
+use crate::env::{Env, Upvalues};
+use crate::value::class::Instance;
+use crate::value::generator::Generator;
+use crate::{value::Value, Interpreter, TailCall};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Arity {
+    Fixed(usize),
+    /// At least this many arguments, with any extra ones collected.
+    Variadic(usize),
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Arity::Fixed(1) => write!(f, "1 argument"),
+            Arity::Fixed(arity) => write!(f, "{arity} arguments"),
+            Arity::Variadic(1) => write!(f, "at least 1 argument"),
+            Arity::Variadic(arity) => write!(f, "at least {arity} arguments"),
+        }
+    }
+}
+
+/// A function implemented in Rust, which gets the location of the call to
+/// report errors at.
+pub type NativeFn = fn(&mut Interpreter, Vec<Value>, &Location) -> Result<Value>;
 
 pub trait Callable {
-    fn arity(&self, paren_loc: &Location) -> Result<usize>;
-    fn call(&self, interpreter: &mut Interpreter, args: Vec<Value>, paren_loc: &Location) -> Result<Value>;
+    fn arity(&self, paren_loc: &Location) -> Result<Arity>;
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        args: Vec<Value>,
+        named_args: Vec<(Token, Value)>,
+        paren_loc: &Location,
+    ) -> Result<Value>;
 }
 
 impl Callable for Value {
-    fn call(&self, interpreter: &mut Interpreter, args: Vec<Value>, paren_loc: &Location) -> Result<Value> {
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        args: Vec<Value>,
+        named_args: Vec<(Token, Value)>,
+        paren_loc: &Location,
+    ) -> Result<Value> {
         let arity = self.arity(paren_loc)?;
-        if args.len() != arity {
+
+        match self {
+            Value::Fn(function, upvalues) => {
+                call_fn(interpreter, function.clone(), upvalues.clone(), args, named_args, paren_loc.clone())
+            },
+            Value::Class(class) => {
+                let instance = Rc::new(Instance::new(class.clone()));
+                match Instance::bind(&instance, "init") {
+                    Some(init) => {
+                        init.call(interpreter, args, named_args, paren_loc)?;
+                    },
+                    None => {
+                        bind_args(&[], args, named_args, paren_loc)?;
+                    },
+                }
+
+                Ok(Value::Instance(instance))
+            },
+            Value::Instance(instance) => match Instance::bind(instance, "__call") {
+                Some(method) => method.call(interpreter, args, named_args, paren_loc),
+                None => unreachable!("arity() rejects instances that can't be called"),
+            },
+            Value::NativeFn(fn_ptr, _) => {
+                if let Some((name, _)) = named_args.first() {
+                    return error!(
+                        ReportKind::RuntimeError,
+                        "Native functions don't take named arguments.",
+                        name.location()
+                    );
+                }
+
+                match arity {
+                    Arity::Fixed(arity) if args.len() != arity => {
+                        return error!(
+                            ReportKind::RuntimeError,
+                            &format!(
+                                "Expected {arity} arguments but got {}.",
+                                args.len()
+                            ),
+                            paren_loc
+                        );
+                    },
+                    Arity::Variadic(arity) if args.len() < arity => {
+                        return error!(
+                            ReportKind::RuntimeError,
+                            &format!(
+                                "Expected at least {arity} arguments but got {}.",
+                                args.len()
+                            ),
+                            paren_loc
+                        );
+                    },
+                    _ => {},
+                }
+
+                (fn_ptr)(interpreter, args, paren_loc)
+            },
+            _ => unreachable!()
+        }
+    }
+
+    fn arity(&self, paren_loc: &Location) -> Result<Arity> {
+        match self {
+            Value::Class(class) => match class.method("init") {
+                Some(init) => Ok(function_arity(init)),
+                None => Ok(Arity::Fixed(0)),
+            },
+            Value::Fn(function, _) => Ok(function_arity(function)),
+            Value::Instance(instance) => match instance.class.method("__call") {
+                Some(method) => Ok(function_arity(method)),
+                None => error!(ReportKind::TypeError, &format!("{self} is not a function"), paren_loc),
+            },
+            Value::NativeFn(_, arity) => Ok(*arity),
+            _ => error!(ReportKind::TypeError, &format!("{self} is not a function"), paren_loc)
+        }
+    }
+}
+
+/// Calls a function, then keeps making the calls it leaves in tail position
+/// in the same frame.
+fn call_fn(
+    interpreter: &mut Interpreter,
+    mut function: Rc<Function>,
+    mut upvalues: Upvalues,
+    mut args: Vec<Value>,
+    mut named_args: Vec<(Token, Value)>,
+    mut paren_loc: Location,
+) -> Result<Value> {
+    interpreter.check_stack(&paren_loc)?;
+    let pending = interpreter.tail_call.take();
+
+    loop {
+        let bound = bind_args(&function.params, args, named_args, &paren_loc)?;
+        let call_env = Env::with_upvalues(interpreter.globals.clone(), upvalues);
+        let call_env = Rc::new(RefCell::new(call_env));
+
+        interpreter.with_env(call_env.clone(), |interpreter| {
+            // Defaults are evaluated in the function's environment so
+            // they can refer to the parameters before them.
+            for (param, arg) in function.params.iter().zip(bound) {
+                let value = match (arg, &param.default) {
+                    (Some(value), _) => value,
+                    (None, Some(default)) => interpreter.evaluate(default)?,
+                    (None, None) => unreachable!("missing arguments are rejected by bind_args"),
+                };
+
+                interpreter.bind_pattern(&param.pattern, value)?;
+            }
+
+            Ok(())
+        })?;
+
+        if function.generator {
+            let generator = Generator::new(interpreter.fork(call_env), function, &paren_loc);
+            interpreter.tail_call = pending;
+            return Ok(Value::Generator(Rc::new(RefCell::new(generator))));
+        }
+
+        let tail_calls = mem::replace(&mut interpreter.tail_calls, true);
+        let return_value = interpreter.with_env(call_env, |interpreter| {
+            interpreter.call_function(&function)
+        });
+        interpreter.tail_calls = tail_calls;
+        let return_value = return_value?;
+
+        match interpreter.tail_call.take() {
+            Some(TailCall { callee: Value::Fn(next, next_upvalues), args: next_args, named_args: next_named, location }) => {
+                function = next;
+                upvalues = next_upvalues;
+                args = next_args;
+                named_args = next_named;
+                paren_loc = location;
+            },
+            Some(TailCall { callee, args, named_args, location }) => {
+                interpreter.tail_call = pending;
+                return callee.call(interpreter, args, named_args, &location);
+            },
+            None => {
+                interpreter.tail_call = pending;
+                return Ok(return_value);
+            },
+        }
+    }
+}
+
+pub(crate) fn function_arity(function: &Function) -> Arity {
+    params_arity(&function.params)
+}
+
+pub(crate) fn params_arity(params: &[Param]) -> Arity {
+    match params.last() {
+        Some(param) if param.rest => Arity::Variadic(params.len() - 1),
+        _ => Arity::Fixed(params.len()),
+    }
+}
+
+/// Whether a function with these parameters can be called with every number
+/// of arguments that `arity` allows, leaving out ones with default values.
+pub(crate) fn accepts(params: &[Param], arity: Arity) -> bool {
+    let (params, rest) = match params.split_last() {
+        Some((last, params)) if last.rest => (params, true),
+        _ => (params, false),
+    };
+    let required = params.iter().rposition(|param| param.default.is_none()).map_or(0, |i| i + 1);
+
+    match arity {
+        Arity::Fixed(count) => required <= count && (rest || count <= params.len()),
+        Arity::Variadic(count) => required <= count && rest,
+    }
+}
+
+/// Matches positional and named arguments to parameters. Parameters that are
+/// left as `None` have a default value that the caller has to evaluate.
+fn bind_args(
+    params: &[Param],
+    mut args: Vec<Value>,
+    named_args: Vec<(Token, Value)>,
+    paren_loc: &Location,
+) -> Result<Vec<Option<Value>>> {
+    let (params, rest) = match params.split_last() {
+        Some((last, params)) if last.rest => (params, true),
+        _ => (params, false),
+    };
+
+    let rest_args = if args.len() > params.len() {
+        if !rest {
             return error!(
                 ReportKind::RuntimeError,
                 &format!(
-                    "Expected {arity} arguments but got {}.",
+                    "Expected at most {} arguments but got {}.",
+                    params.len(),
                     args.len()
                 ),
                 paren_loc
             );
         }
 
-        match self {
-            Value::Fn(Stmt::Fn(_, fn_args, body, return_expr), env) => {
-                let env = Rc::new(RefCell::new(Env::from_parent(env.clone())));
+        args.split_off(params.len())
+    } else {
+        vec![]
+    };
 
-                {
-                    let mut env_borrow = env.borrow_mut();
+    let mut bound: Vec<Option<Value>> = args.into_iter().map(Some).collect();
+    bound.resize(params.len(), None);
 
-                    for (i, arg) in fn_args.iter().enumerate() {
-                        env_borrow.define(arg.lexeme().to_string(), args[i].clone());
-                    }
-                }
+    let mut unknown = vec![];
+    for (name, value) in named_args {
+        let position = params.iter().position(|param| {
+            matches!(&param.pattern, Pattern::Name(param) if param.lexeme() == name.lexeme())
+        });
 
-                let saved_return_value = interpreter.return_value.clone();
-                let mut return_value = Value::Null;
-                interpreter.with_env(env, |interpreter| {
-                    interpreter.interpret(body.clone())?;
+        match position {
+            Some(i) if bound[i].is_some() => {
+                return error!(
+                    ReportKind::RuntimeError,
+                    &format!("Got multiple values for parameter '{}'.", name.lexeme()),
+                    name.location()
+                );
+            },
+            Some(i) => bound[i] = Some(value),
+            None => unknown.push(name.lexeme().to_string()),
+        }
+    }
 
-                    if let Some(ret) = &interpreter.return_value {
-                        return_value = ret.clone();
-                        interpreter.return_value = saved_return_value;
-                        return Ok(());
-                    }
+    if !unknown.is_empty() {
+        return error!(
+            ReportKind::RuntimeError,
+            &format!("Unknown {}.", describe_params(&unknown)),
+            paren_loc
+        );
+    }
 
-                    return_value = interpreter.evaluate(return_expr)?;
-                    Ok(())
-                })?;
+    let missing: Vec<String> = params
+        .iter()
+        .zip(&bound)
+        .filter(|(param, arg)| arg.is_none() && param.default.is_none())
+        .map(|(param, _)| param.pattern.to_string())
+        .collect();
 
-                Ok(return_value)
-            },
-            Value::NativeFn(fn_ptr, _) => (fn_ptr)(interpreter, args),
-            _ => unreachable!()
-        }
+    if !missing.is_empty() {
+        return error!(
+            ReportKind::RuntimeError,
+            &format!("No value given for {}.", describe_params(&missing)),
+            paren_loc
+        );
     }
 
-    fn arity(&self, paren_loc: &Location) -> Result<usize> {
-        match self {
-            Value::Fn(Stmt::Fn(_, fn_args, _, _), _) => Ok(fn_args.len()),
-            Value::NativeFn(_, arity) => Ok(*arity),
-            _ => error!(ReportKind::TypeError, &format!("{self} is not a function"), paren_loc)
-        }
+    if rest {
+        bound.push(Some(Value::List(Rc::new(RefCell::new(rest_args)))));
+    }
+
+    Ok(bound)
+}
+
+fn describe_params(names: &[String]) -> String {
+    let list = names
+        .iter()
+        .map(|name| format!("'{name}'"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if names.len() == 1 {
+        format!("parameter {list}")
+    } else {
+        format!("parameters {list}")
     }
 }
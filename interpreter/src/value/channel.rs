@@ -0,0 +1,49 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use common::errors::Result;
+use common::location::Location;
+
+use crate::{value::Value, Interpreter};
+
+/// An unbuffered channel between fibers. Sending waits until the value has been
+/// received, and receiving waits until there is a value.
+#[derive(Default)]
+pub struct Channel {
+    queue: RefCell<VecDeque<Value>>,
+    sent: Cell<u64>,
+    received: Cell<u64>,
+}
+
+impl Channel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn send(&self, interpreter: &mut Interpreter, value: Value, location: &Location) -> Result<()> {
+        let ticket = self.sent.get();
+        self.sent.set(ticket + 1);
+        self.queue.borrow_mut().push_back(value);
+        interpreter.scheduler.progress();
+
+        interpreter.block_until(|| self.received.get() > ticket, location)
+    }
+
+    pub fn recv(&self, interpreter: &mut Interpreter, location: &Location) -> Result<Value> {
+        interpreter.block_until(|| !self.queue.borrow().is_empty(), location)?;
+
+        let value = self.queue.borrow_mut().pop_front().unwrap();
+        self.received.set(self.received.get() + 1);
+        interpreter.scheduler.progress();
+
+        Ok(value)
+    }
+}
@@ -0,0 +1,86 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use common::stmt::Function;
+
+use crate::env::{Env, Local};
+use crate::value::Value;
+use crate::value::interface::Interface;
+
+pub struct Class {
+    pub name: String,
+    methods: HashMap<String, Rc<Function>>,
+    /// The interfaces the class declared it implements.
+    interfaces: Vec<Rc<Interface>>,
+    /// The scope around its methods, with the variables the class captured
+    /// where it was declared.
+    env: Rc<RefCell<Env>>,
+    /// The variables each method captures from that scope once `this` is
+    /// bound, by function id.
+    captures: HashMap<usize, Vec<Local>>,
+}
+
+impl Class {
+    pub fn new(
+        name: String,
+        methods: HashMap<String, Rc<Function>>,
+        interfaces: Vec<Rc<Interface>>,
+        env: Rc<RefCell<Env>>,
+        captures: HashMap<usize, Vec<Local>>,
+    ) -> Self {
+        Self { name, methods, interfaces, env, captures }
+    }
+
+    pub fn method(&self, name: &str) -> Option<&Rc<Function>> {
+        self.methods.get(name)
+    }
+
+    pub fn implements(&self, interface: &Rc<Interface>) -> bool {
+        self.interfaces.iter().any(|implemented| Rc::ptr_eq(implemented, interface))
+    }
+}
+
+pub struct Instance {
+    pub class: Rc<Class>,
+    fields: RefCell<HashMap<String, Value>>,
+}
+
+impl Instance {
+    pub fn new(class: Rc<Class>) -> Self {
+        Self {
+            class,
+            fields: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.fields.borrow().get(name).cloned()
+    }
+
+    pub fn set(&self, name: &str, value: Value) {
+        self.fields.borrow_mut().insert(name.to_string(), value);
+    }
+
+    /// Looks up a method and binds `this` to the instance.
+    pub fn bind(instance: &Rc<Instance>, name: &str) -> Option<Value> {
+        let method = instance.class.method(name)?;
+
+        let mut env = Env::from_parent(instance.class.env.clone());
+        env.define("this", Value::Instance(instance.clone()));
+
+        let upvalues = match instance.class.captures.get(&method.id) {
+            Some(captures) => env.capture_all(captures),
+            None => Rc::new([]),
+        };
+        Some(Value::Fn(method.clone(), upvalues))
+    }
+}
@@ -0,0 +1,47 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+
+use common::errors::FluetError;
+use common::location::Location;
+
+/// An error as seen by scripts, either created with `error()` or converted
+/// from a runtime error when it is caught.
+#[derive(Clone, Debug)]
+pub struct ErrorValue {
+    pub kind: String,
+    pub message: String,
+    pub location: Location,
+}
+
+impl ErrorValue {
+    pub fn new(kind: &str, message: String) -> Self {
+        Self {
+            kind: kind.to_string(),
+            message,
+            location: Location::default(),
+        }
+    }
+}
+
+impl From<&FluetError> for ErrorValue {
+    fn from(err: &FluetError) -> Self {
+        Self {
+            kind: err.kind.name().to_string(),
+            message: err.message.clone(),
+            location: err.location.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ErrorValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.message)
+    }
+}
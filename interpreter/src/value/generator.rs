@@ -0,0 +1,137 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::mem;
+use std::rc::Rc;
+
+use common::errors::{ReportKind, Result};
+use common::location::Location;
+use common::stmt::Function;
+use corosensei::stack::{DefaultStack, Stack};
+use corosensei::{Coroutine, CoroutineResult, Yielder};
+
+use crate::{value::Value, Interpreter};
+
+/// Generator bodies and fibers run on a stack of their own, so they can be
+/// suspended anywhere in the interpreter's recursion. Every suspended one
+/// holds on to its stack, which only reserves address space; pages are
+/// committed as the stack grows into them.
+const STACK_SIZE: usize = 16 * 1024 * 1024;
+
+/// Room left at the end of a stack for the frames between two function calls,
+/// so running out of it is reported before the guard page is hit.
+const RED_ZONE: usize = 256 * 1024;
+
+/// The most stacks of finished generators kept around for new ones.
+const POOLED_STACKS: usize = 16;
+
+thread_local! {
+    static STACKS: RefCell<Vec<DefaultStack>> = const { RefCell::new(vec![]) };
+}
+
+pub(crate) fn allocate_stack(location: &Location) -> Result<DefaultStack> {
+    if let Some(stack) = STACKS.with(|stacks| stacks.borrow_mut().pop()) {
+        return Ok(stack);
+    }
+
+    match DefaultStack::new(STACK_SIZE) {
+        Ok(stack) => Ok(stack),
+        Err(err) => error!(
+            ReportKind::RuntimeError,
+            &format!("Couldn't allocate a stack: {err}"),
+            location
+        ),
+    }
+}
+
+/// The lowest address calls made on `stack` may reach.
+pub(crate) fn stack_limit(stack: &DefaultStack) -> usize {
+    stack.limit().get() + RED_ZONE
+}
+
+fn release_stack(stack: DefaultStack) {
+    STACKS.with(|stacks| {
+        let mut stacks = stacks.borrow_mut();
+        if stacks.len() < POOLED_STACKS {
+            stacks.push(stack);
+        }
+    });
+}
+
+/// The fiber resuming a generator, if any, which the generator's body blocks
+/// when it waits on a channel.
+pub(crate) type Resumer = Option<*const Yielder<(), ()>>;
+
+type GeneratorCoroutine = Coroutine<Resumer, Value, (Result<Value>, Option<Value>), DefaultStack>;
+
+enum State {
+    /// Not resumed yet, so it doesn't have a stack.
+    Created(Box<Interpreter>, Rc<Function>, Location),
+    Suspended(GeneratorCoroutine),
+    Finished,
+}
+
+/// The suspended execution of a generator function's body.
+pub struct Generator {
+    state: State,
+}
+
+impl Generator {
+    /// Creates a generator that runs `function`'s body on `interpreter`, whose
+    /// environment should already have the arguments bound.
+    pub(crate) fn new(interpreter: Interpreter, function: Rc<Function>, paren_loc: &Location) -> Self {
+        Self { state: State::Created(Box::new(interpreter), function, paren_loc.clone()) }
+    }
+
+    /// Runs the generator until it yields its next value. Returns `None` once
+    /// the body has finished.
+    pub fn resume(&mut self, interpreter: &mut Interpreter) -> Result<Option<Value>> {
+        let mut coroutine = match mem::replace(&mut self.state, State::Finished) {
+            State::Created(mut body, function, location) => {
+                let stack = allocate_stack(&location)?;
+                body.stack_limit = Some(stack_limit(&stack));
+                Coroutine::with_stack(stack, move |yielder: &Yielder<Resumer, Value>, fiber| {
+                    body.yielder = Some(yielder);
+                    body.fiber = fiber;
+                    let result = body.call_function(&function);
+                    (result, body.thrown.take())
+                })
+            },
+            State::Suspended(coroutine) => coroutine,
+            State::Finished => return Ok(None),
+        };
+
+        match coroutine.resume(interpreter.fiber) {
+            CoroutineResult::Yield(value) => {
+                self.state = State::Suspended(coroutine);
+                Ok(Some(value))
+            },
+            CoroutineResult::Return((result, thrown)) => {
+                release_stack(coroutine.into_stack());
+
+                // Let a `catch` outside of the generator see the thrown value
+                if thrown.is_some() {
+                    interpreter.thrown = thrown;
+                }
+
+                result.map(|_| None)
+            },
+        }
+    }
+}
+
+impl Drop for Generator {
+    fn drop(&mut self) {
+        // Unwind what's left of the body so its stack can be reused
+        if let State::Suspended(mut coroutine) = mem::replace(&mut self.state, State::Finished) {
+            coroutine.force_unwind();
+            release_stack(coroutine.into_stack());
+        }
+    }
+}
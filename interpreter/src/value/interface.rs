@@ -0,0 +1,22 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use crate::value::callable::Arity;
+
+/// A set of methods that classes can declare they implement.
+pub struct Interface {
+    pub name: String,
+    /// The names of the required methods and how many arguments they take.
+    pub methods: Vec<(String, Arity)>,
+}
+
+impl Interface {
+    pub fn new(name: String, methods: Vec<(String, Arity)>) -> Self {
+        Self { name, methods }
+    }
+}
@@ -0,0 +1,83 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::value::Value;
+
+/// A value used as a map key. Unlike with `==`, NaN is equal to itself here,
+/// so that every key that is inserted can be found again.
+#[derive(Clone)]
+pub struct Key(pub Value);
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        same_key(&self.0, &other.0)
+    }
+}
+
+impl Eq for Key {}
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+fn same_key(lhs: &Value, rhs: &Value) -> bool {
+    match (lhs, rhs) {
+        (Value::Number(lhs), Value::Number(rhs)) => lhs == rhs || lhs.is_nan() && rhs.is_nan(),
+        (Value::Tuple(lhs), Value::Tuple(rhs)) => {
+            lhs.len() == rhs.len() && lhs.iter().zip(rhs.iter()).all(|(lhs, rhs)| same_key(lhs, rhs))
+        },
+        (lhs, rhs) => lhs == rhs,
+    }
+}
+
+/// A map from keys to values, which keeps the order the keys were first
+/// inserted in.
+#[derive(Default)]
+pub struct Map {
+    entries: Vec<(Value, Value)>,
+    indices: HashMap<Key, usize>,
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &Value) -> Option<&Value> {
+        let index = self.indices.get(&Key(key.clone()))?;
+        Some(&self.entries[*index].1)
+    }
+
+    /// Sets the value of a key, keeping its place if it was already inserted.
+    pub fn insert(&mut self, key: Value, value: Value) {
+        match self.indices.get(&Key(key.clone())) {
+            Some(&index) => self.entries[index].1 = value,
+            None => {
+                self.indices.insert(Key(key.clone()), self.entries.len());
+                self.entries.push((key, value));
+            },
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> &[(Value, Value)] {
+        &self.entries
+    }
+}
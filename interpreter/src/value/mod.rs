@@ -7,35 +7,152 @@
  */
 
 pub mod callable;
+pub mod channel;
+pub mod class;
+pub mod error;
+pub mod generator;
+pub mod interface;
+pub mod map;
 
 use std::cell::RefCell;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem;
 use std::rc::Rc;
-use common::errors::Result;
-use common::stmt::Stmt;
+use common::stmt::Function;
 
-use crate::Interpreter;
-use crate::env::Env;
+use crate::value::callable::{Arity, NativeFn};
+use crate::value::channel::Channel;
+use crate::value::class::{Class, Instance};
+use crate::env::Upvalues;
+use crate::value::error::ErrorValue;
+use crate::value::generator::Generator;
+use crate::value::interface::Interface;
+use crate::value::map::Map;
 
 #[derive(Clone)]
 pub enum Value {
     Bool(bool),
-    Fn(Stmt, Rc<RefCell<Env>>),
-    NativeFn(fn(&mut Interpreter, Vec<Value>) -> Result<Value>, usize),
+    Channel(Rc<Channel>),
+    Class(Rc<Class>),
+    Error(Rc<ErrorValue>),
+    /// A function and the variables it captured where it was created.
+    Fn(Rc<Function>, Upvalues),
+    Generator(Rc<RefCell<Generator>>),
+    Instance(Rc<Instance>),
+    Interface(Rc<Interface>),
+    List(Rc<RefCell<Vec<Value>>>),
+    Map(Rc<RefCell<Map>>),
+    NativeFn(NativeFn, Arity),
     Null,
     Number(f64),
     String(String),
+    Tuple(Rc<Vec<Value>>),
+}
+
+/// Tuples compare structurally, while everything else that lives behind a
+/// reference compares by identity. As with `f64`, `NaN` is not equal to itself,
+/// which is why only `map::Key` is `Eq`.
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Bool(lhs), Value::Bool(rhs)) => lhs == rhs,
+            (Value::Channel(lhs), Value::Channel(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::Class(lhs), Value::Class(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::Error(lhs), Value::Error(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::Fn(lhs, lhs_upvalues), Value::Fn(rhs, rhs_upvalues)) => {
+                Rc::ptr_eq(lhs, rhs) && Rc::ptr_eq(lhs_upvalues, rhs_upvalues)
+            },
+            (Value::Generator(lhs), Value::Generator(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::Instance(lhs), Value::Instance(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::Interface(lhs), Value::Interface(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::List(lhs), Value::List(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::Map(lhs), Value::Map(rhs)) => Rc::ptr_eq(lhs, rhs),
+            (Value::NativeFn(lhs, _), Value::NativeFn(rhs, _)) => std::ptr::fn_addr_eq(*lhs, *rhs),
+            (Value::Null, Value::Null) => true,
+            (Value::Number(lhs), Value::Number(rhs)) => lhs == rhs,
+            (Value::String(lhs), Value::String(rhs)) => lhs == rhs,
+            (Value::Tuple(lhs), Value::Tuple(rhs)) => lhs == rhs,
+            _ => false,
+        }
+    }
+}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Value::Bool(bool) => bool.hash(state),
+            Value::Channel(channel) => Rc::as_ptr(channel).hash(state),
+            Value::Class(class) => Rc::as_ptr(class).hash(state),
+            Value::Error(error) => Rc::as_ptr(error).hash(state),
+            Value::Fn(r#fn, _) => Rc::as_ptr(r#fn).hash(state),
+            Value::Generator(generator) => Rc::as_ptr(generator).hash(state),
+            Value::Instance(instance) => Rc::as_ptr(instance).hash(state),
+            Value::Interface(interface) => Rc::as_ptr(interface).hash(state),
+            Value::List(list) => Rc::as_ptr(list).hash(state),
+            Value::Map(map) => Rc::as_ptr(map).hash(state),
+            Value::NativeFn(fn_ptr, _) => (*fn_ptr as usize).hash(state),
+            Value::Null => {},
+            // 0 and -0 are equal, so they have to hash the same
+            Value::Number(number) if *number == 0.0 => 0.0f64.to_bits().hash(state),
+            // Map keys treat every NaN as the same
+            Value::Number(number) if number.is_nan() => f64::NAN.to_bits().hash(state),
+            Value::Number(number) => number.to_bits().hash(state),
+            Value::String(string) => string.hash(state),
+            Value::Tuple(tuple) => tuple.hash(state),
+        }
+    }
 }
 
 impl fmt::Display for Value {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Value::Bool(bool) => write!(f, "{}", bool),
+            Value::Channel(_) => write!(f, "<chan>"),
+            Value::Class(class) => write!(f, "<class {}>", class.name),
+            Value::Error(error) => write!(f, "{}", error),
             Value::Fn(_, _) => write!(f, "<fn>"),
+            Value::Generator(_) => write!(f, "<generator>"),
+            Value::Instance(instance) => write!(f, "<{} instance>", instance.class.name),
+            Value::Interface(interface) => write!(f, "<interface {}>", interface.name),
+            Value::List(list) => {
+                write!(f, "[")?;
+                for (i, value) in list.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            },
+            Value::Map(map) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in map.borrow().entries().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            },
             Value::NativeFn(_, _) => write!(f, "<native fn>"),
             Value::Null => write!(f, "null"),
             Value::Number(number) => write!(f, "{}", number),
             Value::String(string) => write!(f, "'{}'", string),
+            Value::Tuple(tuple) => {
+                write!(f, "(")?;
+                for (i, value) in tuple.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                if tuple.len() == 1 {
+                    write!(f, ",")?;
+                }
+                write!(f, ")")
+            },
         }
     }
 }
@@ -69,14 +69,25 @@ impl Lexer {
             Some(")") => self.add_token(TokenType::RightParen, None),
             Some("{") => self.add_token(TokenType::LeftBrace, None),
             Some("}") => self.add_token(TokenType::RightBrace, None),
+            Some("[") => self.add_token(TokenType::LeftBracket, None),
+            Some("]") => self.add_token(TokenType::RightBracket, None),
+            Some("@") => self.add_token(TokenType::At, None),
             Some(",") => self.add_token(TokenType::Comma, None),
-            Some("-") => self.add_token(TokenType::Minus, None),
             Some("%") => self.add_token(TokenType::Percent, None),
             Some("+") => self.add_token(TokenType::Plus, None),
             Some(";") => self.add_token(TokenType::Semicolon, None),
             Some("*") => self.add_token(TokenType::Star, None),
 
             // One or two character tokens
+            Some("-") => {
+                let token = if self.match_char(">") {
+                    TokenType::MinusGreater
+                } else {
+                    TokenType::Minus
+                };
+
+                self.add_token(token, None);
+            }
             Some("!") => {
                 let token = if self.match_char("=") {
                     TokenType::BangEqual
@@ -122,6 +133,15 @@ impl Lexer {
 
                 self.add_token(token, None);
             }
+            Some("?") => {
+                if self.match_char("?") {
+                    self.add_token(TokenType::QuestionQuestion, None);
+                } else if self.match_char(".") {
+                    self.add_token(TokenType::QuestionDot, None);
+                } else {
+                    self.unexpected_character();
+                }
+            }
             Some("&") => {
                 let token = if self.match_char("&") {
                     TokenType::LogicalAnd
@@ -134,6 +154,8 @@ impl Lexer {
             Some("|") => {
                 let token = if self.match_char("|") {
                     TokenType::LogicalOr
+                } else if self.match_char(">") {
+                    TokenType::PipeGreater
                 } else {
                     TokenType::BitwiseOr
                 };
@@ -142,13 +164,26 @@ impl Lexer {
             }
             Some("/") => {
                 if self.match_char("/") {
+                    // `///` starts a doc comment, but a line of slashes doesn't
+                    let doc = self.peek() == "/" && self.peek_nth(2) != "/";
+
                     // A comment goes until the end of the line.
                     while self.peek() != "\n" && !self.is_at_end() {
                         self.advance();
                     }
+
+                    if doc {
+                        let lexeme = self.lexeme();
+                        let text = lexeme["///".len()..].strip_prefix(' ').unwrap_or(&lexeme["///".len()..]);
+                        self.add_token(TokenType::DocComment, Some(Literal::String(text.trim_end().to_string())));
+                    }
                 } else if self.match_char("*") {
+                    // `/**` starts a doc comment, but `/**/` doesn't
+                    let doc = self.peek() == "*" && self.peek_nth(2) != "/";
+                    let (row, column) = (self.row, self.column);
+
                     let mut nesting_counter = 0;
-                    loop {
+                    while !self.is_at_end() {
                         if self.peek() == "/" && self.peek_nth(2) == "*" {
                             self.advance();
                             self.advance();
@@ -164,11 +199,15 @@ impl Lexer {
                         } else if self.match_char("\n") {
                             self.row += 1;
                             self.column = 0;
-                            self.advance();
                         } else {
                             self.advance();
                         }
                     }
+
+                    if doc {
+                        let text = doc_block_text(&self.lexeme());
+                        self.add_token_at(TokenType::DocComment, Some(Literal::String(text)), row, column);
+                    }
                 } else {
                     self.add_token(TokenType::Slash, None);
                 }
@@ -187,26 +226,30 @@ impl Lexer {
 
             Some(i) if util::is_digit(i) => self.number(),
             Some(i) if util::is_alpha(i) => self.identifier(),
-            _ => eprintln!(
-                "{}",
-                report_error(
-                    ReportKind::SyntaxError,
-                    None,
-                    &format!(
-                        "Unexpected character at {}:{}:{}",
-                        self.filename, self.row, self.column
-                    ),
-                    &Location {
-                        filename: String::new(),
-                        row: 0,
-                        column: 0,
-                        line: String::new(),
-                    }
-                )
-            ),
+            _ => self.unexpected_character(),
         }
     }
 
+    fn unexpected_character(&self) {
+        eprintln!(
+            "{}",
+            report_error(
+                ReportKind::SyntaxError,
+                None,
+                &format!(
+                    "Unexpected character at {}:{}:{}",
+                    self.filename, self.row, self.column
+                ),
+                &Location {
+                    filename: String::new(),
+                    row: 0,
+                    column: 0,
+                    line: String::new(),
+                }
+            )
+        );
+    }
+
     fn string(&mut self, quote: &str) {
         while self.peek() != quote && !self.is_at_end() {
             if self.peek() == "\n" {
@@ -229,7 +272,7 @@ impl Lexer {
         let mut value = String::new();
 
         for (i, grapheme) in self.source.graphemes(true).enumerate() {
-            if i >= self.start + 1 && i < self.current - 1 {
+            if i > self.start && i < self.current - 1 {
                 value.push_str(grapheme);
             }
         }
@@ -238,7 +281,8 @@ impl Lexer {
     }
 
     fn dot(&mut self) {
-        if util::is_digit(self.peek()) {
+        // After an operand, `.0` is a tuple field access rather than a number
+        if util::is_digit(self.peek()) && !self.after_operand() {
             while util::is_digit(self.peek()) {
                 self.advance();
             }
@@ -254,6 +298,10 @@ impl Lexer {
             // Should always be a valid f64
             let number = text.parse().unwrap();
             self.add_token(TokenType::Number, Some(Literal::Number(number)));
+        } else if self.peek() == "." && self.peek_nth(2) == "." {
+            self.advance();
+            self.advance();
+            self.add_token(TokenType::DotDotDot, None);
         } else {
             self.add_token(TokenType::Dot, None);
         }
@@ -264,8 +312,12 @@ impl Lexer {
             self.advance();
         }
 
-        // Look for the fractional part
-        if self.peek() == "." && util::is_digit(self.peek_nth(2)) {
+        // Look for the fractional part, unless this is a tuple field like `t.0`
+        let is_field = matches!(
+            self.tokens.last().map(|token| token.token_type()),
+            Some(TokenType::Dot | TokenType::QuestionDot)
+        );
+        if !is_field && self.peek() == "." && util::is_digit(self.peek_nth(2)) {
             // Consume the "."
             self.advance();
 
@@ -318,6 +370,19 @@ impl Lexer {
         self.add_token(token_type, literal);
     }
 
+    fn after_operand(&self) -> bool {
+        matches!(
+            self.tokens.last().map(|token| token.token_type()),
+            Some(
+                TokenType::Identifier
+                    | TokenType::Number
+                    | TokenType::String
+                    | TokenType::RightParen
+                    | TokenType::RightBracket
+            )
+        )
+    }
+
     fn advance(&mut self) -> Option<&str> {
         let current = self.source.graphemes(true).nth(self.current);
         self.current += 1;
@@ -344,7 +409,7 @@ impl Lexer {
     }
 
     fn peek_nth(&mut self, chars: usize) -> &str {
-        if self.current + chars - 1 >= self.source.graphemes(true).count() {
+        if self.current + chars > self.source.graphemes(true).count() {
             return "\0";
         }
 
@@ -352,7 +417,7 @@ impl Lexer {
         self.source.graphemes(true).nth(self.current + chars - 1).unwrap()
     }
 
-    fn add_token(&mut self, token_type: TokenType, literal: Option<Literal>) {
+    fn lexeme(&self) -> String {
         let mut text = String::new();
 
         for (i, grapheme) in self.source.graphemes(true).enumerate() {
@@ -361,14 +426,49 @@ impl Lexer {
             }
         }
 
+        text
+    }
+
+    fn add_token(&mut self, token_type: TokenType, literal: Option<Literal>) {
+        self.add_token_at(token_type, literal, self.row, self.column);
+    }
+
+    /// Adds a token that starts on an earlier line than the current one.
+    fn add_token_at(&mut self, token_type: TokenType, literal: Option<Literal>, row: usize, column: usize) {
         self.tokens.push(Token::new(
             token_type,
-            text,
+            self.lexeme(),
             self.filename.clone(),
-            self.lines[self.row - 1].to_string(),
-            self.row,
-            self.column,
+            self.lines[row - 1].to_string(),
+            row,
+            column,
             literal,
         ));
     }
 }
+
+/// The text of a `/** */` comment, without the delimiters and the `*` that
+/// lines inside it may start with.
+fn doc_block_text(lexeme: &str) -> String {
+    let inner = lexeme
+        .strip_prefix("/**")
+        .unwrap_or(lexeme)
+        .strip_suffix("*/")
+        .unwrap_or(lexeme);
+
+    let lines: Vec<&str> = inner
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            match line.strip_prefix('*') {
+                Some(line) => line.strip_prefix(' ').unwrap_or(line),
+                None => line,
+            }
+        })
+        .collect();
+
+    // Drop the lines that only held the delimiters
+    let first = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+    let last = lines.iter().rposition(|line| !line.is_empty()).map_or(first, |last| last + 1);
+    lines[first..last].join("\n")
+}
@@ -9,26 +9,68 @@
 #[macro_use]
 extern crate common;
 
+use std::collections::HashMap;
+use std::rc::Rc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
-use common::errors::{report_error, FluetError, ReportKind, Result};
-use common::expr::Expr;
-use common::stmt::Stmt;
+use common::errors::{report_error, ReportKind, Result};
+use common::expr::{Arg, Expr};
+use common::pattern::Pattern;
+use common::stmt::{Attribute, Doc, Function, Param, Signature, Stmt};
 use common::token::{Literal, Token, TokenType};
+use common::types::TypeExpr;
 
-fn next_expr_id() -> usize {
-    static mut ID: AtomicUsize = AtomicUsize::new(0);
-    unsafe { ID.fetch_add(1, Ordering::AcqRel) }
+/// Ids are unique across parsers. They label the expressions, functions and
+/// classes the resolver records something about.
+fn next_id() -> usize {
+    static ID: AtomicUsize = AtomicUsize::new(0);
+    ID.fetch_add(1, Ordering::AcqRel)
 }
 
 pub struct Parser {
     tokens: Vec<Token>,
     current: usize,
+    /// Whether each function currently being parsed contains a `yield`,
+    /// innermost last.
+    function_yields: Vec<bool>,
+    /// Doc comments, by the index of the token after them.
+    docs: HashMap<usize, Rc<Doc>>,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, current: 0 }
+        // Doc comments are only kept for the declarations they come before,
+        // everywhere else they are ignored like other comments
+        let mut docs: HashMap<usize, Doc> = HashMap::new();
+        let mut rest = vec![];
+        for token in tokens {
+            if token.token_type() != TokenType::DocComment {
+                rest.push(token);
+                continue;
+            }
+
+            let text = match token.literal() {
+                Some(Literal::String(text)) => text.clone(),
+                _ => String::new(),
+            };
+
+            match docs.get_mut(&rest.len()) {
+                Some(doc) => {
+                    doc.text.push('\n');
+                    doc.text.push_str(&text);
+                },
+                None => {
+                    docs.insert(rest.len(), Doc { token, text });
+                },
+            }
+        }
+
+        Self {
+            tokens: rest,
+            current: 0,
+            function_yields: vec![],
+            docs: docs.into_iter().map(|(index, doc)| (index, Rc::new(doc))).collect(),
+        }
     }
 
     pub fn parse(&mut self) -> Result<Vec<Stmt>> {
@@ -38,10 +80,9 @@ impl Parser {
             match statement {
                 Ok(stmt) => statements.push(stmt),
                 Err(err) => {
-                    match self.synchronize() {
-                        Ok(_) => continue,
-                        Err(_) => return Err(err),
-                    };
+                    if !self.synchronize() {
+                        return Err(err);
+                    }
                 }
             }
         }
@@ -72,33 +113,40 @@ impl Parser {
             }
         }
 
-        return Ok((statements, Box::new(expr)));
+        Ok((statements, Box::new(expr)))
     }
 
-    fn synchronize(&mut self) -> Result<()> {
+    /// Returns `false` if the end of the input was reached before finding a
+    /// statement boundary.
+    fn synchronize(&mut self) -> bool {
         self.advance();
 
         while !self.is_at_end() {
             if self.previous().token_type() == TokenType::Semicolon {
-                return Ok(());
+                return true;
             }
 
             // TODO: add more cases
             match self.peek().token_type() {
-                TokenType::Class
+                TokenType::At
+                | TokenType::Class
                 | TokenType::Fn
                 | TokenType::For
                 | TokenType::If
+                | TokenType::Interface
                 | TokenType::Let
                 | TokenType::Return
-                | TokenType::While => return Ok(()),
+                | TokenType::Throw
+                | TokenType::Try
+                | TokenType::While
+                | TokenType::Yield => return true,
                 _ => {}
             }
 
             self.advance();
         }
 
-        Err(FluetError("".to_string()))
+        false
     }
 
     fn expression(&mut self) -> Result<Expr> {
@@ -106,40 +154,197 @@ impl Parser {
     }
 
     fn declaration(&mut self) -> Result<Stmt> {
+        let doc = self.doc();
+        let attributes = self.attributes()?;
+        if self.match_token(TokenType::Class) {
+            return self.class_declaration(doc, attributes);
+        }
         if self.match_token(TokenType::Fn) {
-            return self.function("function");
+            return self.function("function", doc, attributes);
+        }
+        if let Some(attribute) = attributes.first() {
+            return error!(
+                ReportKind::SyntaxError,
+                "Attributes can only be applied to functions and classes.",
+                attribute.name.location()
+            );
+        }
+
+        if self.match_token(TokenType::Interface) {
+            return self.interface_declaration(doc);
         }
         if self.match_token(TokenType::Let) {
-            return self.let_declaration();
+            return self.let_declaration(doc);
         }
 
         self.statement()
     }
 
-    fn function(&mut self, kind: &str) -> Result<Stmt> {
+    /// The doc comment before the current token, if any.
+    fn doc(&self) -> Option<Rc<Doc>> {
+        self.docs.get(&self.current).cloned()
+    }
+
+    /// Parses the `@name` and `@name(arguments)` before a declaration.
+    fn attributes(&mut self) -> Result<Vec<Attribute>> {
+        let mut attributes = vec![];
+        while self.match_token(TokenType::At) {
+            let name = self.consume(TokenType::Identifier, "Expected attribute name after '@'.")?;
+
+            let mut args = vec![];
+            if self.match_token(TokenType::LeftParen) && !self.match_token(TokenType::RightParen) {
+                loop {
+                    let arg = self.advance();
+                    match (arg.token_type(), arg.literal()) {
+                        (
+                            TokenType::False | TokenType::Null | TokenType::Number | TokenType::String | TokenType::True,
+                            Some(literal),
+                        ) => args.push(literal.clone()),
+                        _ => return error!(
+                            ReportKind::SyntaxError,
+                            "Attribute arguments must be literals.",
+                            arg.location()
+                        ),
+                    }
+
+                    if !self.match_token(TokenType::Comma) { break; }
+                }
+
+                self.consume(TokenType::RightParen, "Expected ')' after attribute arguments.")?;
+            }
+
+            attributes.push(Attribute { name, args });
+        }
+
+        Ok(attributes)
+    }
+
+    fn class_declaration(&mut self, doc: Option<Rc<Doc>>, attributes: Vec<Attribute>) -> Result<Stmt> {
+        let name = self.consume(TokenType::Identifier, "Expected class name.")?;
+
+        let mut interfaces = vec![];
+        if self.match_token(TokenType::Colon) {
+            loop {
+                let interface = self.consume(TokenType::Identifier, "Expected interface name.")?;
+                interfaces.push(Expr::Variable(next_id(), interface));
+                if !self.match_token(TokenType::Comma) { break; }
+            }
+        }
+
+        self.consume(TokenType::LeftBrace, "Expected '{' before class body.")?;
+
+        let mut methods = vec![];
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let doc = self.doc();
+            let attributes = self.attributes()?;
+            self.consume(TokenType::Fn, "Expected 'fn' before method.")?;
+            let name = self.consume(TokenType::Identifier, "Expected method name.")?;
+            self.consume(TokenType::LeftParen, "Expected '(' after method name.")?;
+            methods.push(self.function_body(name, "method", doc, attributes)?);
+        }
+
+        self.consume(TokenType::RightBrace, "Expected '}' after class body.")?;
+        Ok(Stmt::Class(next_id(), name, interfaces, methods, doc, attributes))
+    }
+
+    fn interface_declaration(&mut self, doc: Option<Rc<Doc>>) -> Result<Stmt> {
+        let name = self.consume(TokenType::Identifier, "Expected interface name.")?;
+        self.consume(TokenType::LeftBrace, "Expected '{' before interface body.")?;
+
+        let mut methods = vec![];
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let doc = self.doc();
+            self.consume(TokenType::Fn, "Expected 'fn' before method.")?;
+            let name = self.consume(TokenType::Identifier, "Expected method name.")?;
+            self.consume(TokenType::LeftParen, "Expected '(' after method name.")?;
+            let (params, returns) = self.parameters()?;
+            self.consume(TokenType::Semicolon, "Expected ';' after method signature.")?;
+            methods.push(Signature { name, params, returns, doc });
+        }
+
+        self.consume(TokenType::RightBrace, "Expected '}' after interface body.")?;
+        Ok(Stmt::Interface(name, methods, doc))
+    }
+
+    fn function(&mut self, kind: &str, doc: Option<Rc<Doc>>, attributes: Vec<Attribute>) -> Result<Stmt> {
         let name = self.consume(TokenType::Identifier, &format!("Expected {kind} name."))?;
         self.consume(TokenType::LeftParen, &format!("Expected '(' after {kind} name."))?;
 
-        let mut args = vec![];
+        Ok(Stmt::Fn(self.function_body(name, kind, doc, attributes)?))
+    }
+
+    /// Parses the parameters and body of a function, after the opening paren.
+    fn function_body(
+        &mut self,
+        name: Token,
+        kind: &str,
+        doc: Option<Rc<Doc>>,
+        attributes: Vec<Attribute>,
+    ) -> Result<Rc<Function>> {
+        let (params, returns) = self.parameters()?;
+        let open = self.consume(TokenType::LeftBrace, &format!("Expected '{{' after {kind} body."))?;
+
+        self.function_yields.push(false);
+        let body = self.block_like();
+        let generator = self.function_yields.pop().unwrap_or_default();
+        let (statements, expr) = body?;
+        let close = self.consume(TokenType::RightBrace, &format!("Expected '}}' after {kind} body."))?;
+
+        Ok(Rc::new(Function {
+            id: next_id(),
+            name,
+            params,
+            returns,
+            body: statements,
+            braces: (open, close),
+            ret: *expr,
+            generator,
+            doc,
+            attributes,
+        }))
+    }
+
+    /// Parses parameters after the opening paren, and the return type after
+    /// them.
+    fn parameters(&mut self) -> Result<(Vec<Param>, Option<TypeExpr>)> {
+        let mut params = vec![];
         if !self.check(TokenType::RightParen) {
             loop {
-                args.push(self.consume(TokenType::Identifier, "Expected argument name.")?);
+                let rest = self.match_token(TokenType::DotDotDot);
+                let pattern = self.pattern()?;
+                let annotation = self.annotation()?;
+                let default = if !rest && self.match_token(TokenType::Equal) {
+                    Some(self.expression()?)
+                } else {
+                    None
+                };
+
+                if rest && !self.check(TokenType::RightParen) {
+                    return error!(
+                        ReportKind::SyntaxError,
+                        "A rest parameter must be the last parameter.",
+                        pattern.token().location()
+                    );
+                }
+
+                params.push(Param { pattern, annotation, default, rest });
                 if !self.match_token(TokenType::Comma) { break; }
             }
         }
 
-        self.consume(TokenType::RightParen, "Expected ')' after arguments.")?;
-        self.consume(TokenType::LeftBrace, &format!("Expected '{{' after {kind} body."))?;
-
-        let (statements, expr) = self.block_like()?;
+        self.consume(TokenType::RightParen, "Expected ')' after parameters.")?;
+        let returns = if self.match_token(TokenType::MinusGreater) {
+            Some(self.type_expr()?)
+        } else {
+            None
+        };
 
-        let r#fn = Stmt::Fn(name, args, statements, *expr);
-        self.consume(TokenType::RightBrace, &format!("Expected '}}' after {kind} body."))?;
-        Ok(r#fn)
+        Ok((params, returns))
     }
 
-    fn let_declaration(&mut self) -> Result<Stmt> {
-        let name = self.consume(TokenType::Identifier, "Expected variable name.")?;
+    fn let_declaration(&mut self, doc: Option<Rc<Doc>>) -> Result<Stmt> {
+        let pattern = self.pattern()?;
+        let annotation = self.annotation()?;
 
         let mut initializer = Expr::Literal(Literal::Null);
         if self.match_token(TokenType::Equal) {
@@ -150,16 +355,141 @@ impl Parser {
             TokenType::Semicolon,
             "Expected ';' after variable declaration.",
         )?;
-        Ok(Stmt::Let(name, initializer))
+        Ok(Stmt::Let(pattern, annotation, initializer, doc))
+    }
+
+    /// An optional `: type` after a variable or parameter.
+    fn annotation(&mut self) -> Result<Option<TypeExpr>> {
+        if self.match_token(TokenType::Colon) {
+            Ok(Some(self.type_expr()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn type_expr(&mut self) -> Result<TypeExpr> {
+        if self.match_token(TokenType::Fn) {
+            let keyword = self.previous();
+            self.consume(TokenType::LeftParen, "Expected '(' after 'fn'.")?;
+            let params = self.type_list(TokenType::RightParen)?;
+            self.consume(TokenType::RightParen, "Expected ')' after parameter types.")?;
+            self.consume(TokenType::MinusGreater, "Expected '->' after parameter types.")?;
+            return Ok(TypeExpr::Fn(keyword, params, Box::new(self.type_expr()?)));
+        }
+
+        if self.match_token(TokenType::LeftBracket) {
+            let bracket = self.previous();
+            let element = self.type_expr()?;
+            self.consume(TokenType::RightBracket, "Expected ']' after element type.")?;
+            return Ok(TypeExpr::List(bracket, Box::new(element)));
+        }
+
+        if self.match_token(TokenType::LeftParen) {
+            let paren = self.previous();
+            let elements = self.type_list(TokenType::RightParen)?;
+            self.consume(TokenType::RightParen, "Expected ')' after element types.")?;
+            return Ok(TypeExpr::Tuple(paren, elements));
+        }
+
+        if self.match_any_token(vec![TokenType::Identifier, TokenType::Null]) {
+            return Ok(TypeExpr::Name(self.previous()));
+        }
+
+        error!(
+            ReportKind::SyntaxError,
+            "Expected type.",
+            self.peek().location()
+        )
+    }
+
+    fn type_list(&mut self, closing: TokenType) -> Result<Vec<TypeExpr>> {
+        let mut types = vec![];
+        if !self.check(closing) {
+            loop {
+                types.push(self.type_expr()?);
+                if !self.match_token(TokenType::Comma) || self.check(closing) { break; }
+            }
+        }
+
+        Ok(types)
+    }
+
+    fn pattern(&mut self) -> Result<Pattern> {
+        if self.match_any_token(vec![TokenType::LeftBracket, TokenType::LeftParen]) {
+            let bracket = self.previous();
+            let closing = match bracket.token_type() {
+                TokenType::LeftParen => TokenType::RightParen,
+                _ => TokenType::RightBracket,
+            };
+            let mut elements = vec![];
+            let mut rest = None;
+
+            if !self.check(closing) {
+                loop {
+                    if self.match_token(TokenType::DotDotDot) {
+                        rest = Some(self.consume(TokenType::Identifier, "Expected name after '...'.")?);
+                        break;
+                    }
+
+                    let pattern = self.pattern()?;
+                    elements.push((pattern, self.pattern_default()?));
+                    if !self.match_token(TokenType::Comma) { break; }
+                }
+            }
+
+            self.consume(closing, &format!("Expected '{closing}' after pattern."))?;
+            return Ok(Pattern::List(bracket, elements, rest));
+        }
+
+        if self.match_token(TokenType::LeftBrace) {
+            let brace = self.previous();
+            let mut fields = vec![];
+
+            if !self.check(TokenType::RightBrace) {
+                loop {
+                    let key = self.consume(TokenType::Identifier, "Expected property name.")?;
+                    let pattern = if self.match_token(TokenType::Colon) {
+                        self.pattern()?
+                    } else {
+                        Pattern::Name(key.clone())
+                    };
+
+                    fields.push((key, pattern, self.pattern_default()?));
+                    if !self.match_token(TokenType::Comma) { break; }
+                }
+            }
+
+            self.consume(TokenType::RightBrace, "Expected '}' after object pattern.")?;
+            return Ok(Pattern::Object(brace, fields));
+        }
+
+        let name = self.consume(TokenType::Identifier, "Expected variable name.")?;
+        Ok(Pattern::Name(name))
+    }
+
+    fn pattern_default(&mut self) -> Result<Option<Expr>> {
+        if self.match_token(TokenType::Equal) {
+            Ok(Some(self.expression()?))
+        } else {
+            Ok(None)
+        }
     }
 
     fn statement(&mut self) -> Result<Stmt> {
-        if self.match_token(TokenType::Loop) {
+        if self.match_token(TokenType::For) {
+            self.for_statement()
+        } else if self.match_token(TokenType::Loop) {
             self.loop_statement()
         } else if self.match_token(TokenType::Return) {
             self.return_statement()
+        } else if self.match_token(TokenType::Throw) {
+            self.throw_statement()
+        } else if self.match_token(TokenType::Try) {
+            self.try_statement()
         } else if self.match_token(TokenType::While) {
             self.while_statement()
+        } else if self.match_token(TokenType::Yield) {
+            self.yield_statement()
         } else {
             self.expression_statement()
         }
@@ -171,6 +501,16 @@ impl Parser {
         Ok(Stmt::Expr(expr))
     }
 
+    fn for_statement(&mut self) -> Result<Stmt> {
+        let pattern = self.pattern()?;
+        self.consume(TokenType::In, "Expected 'in' after loop variable.")?;
+        let iterable = self.expression()?;
+        self.consume(TokenType::LeftBrace, "Expected '{' after iterable.")?;
+
+        let body = self.statement_block("loop")?;
+        Ok(Stmt::For(pattern, iterable, body))
+    }
+
     fn loop_statement(&mut self) -> Result<Stmt> {
         self.consume(TokenType::LeftBrace, "Expected '{' after 'loop'.")?;
 
@@ -184,6 +524,7 @@ impl Parser {
     }
 
     fn return_statement(&mut self) -> Result<Stmt> {
+        let keyword = self.previous();
         let value = if self.check(TokenType::Semicolon) {
             Expr::Literal(Literal::Null)
         } else {
@@ -191,10 +532,82 @@ impl Parser {
         };
 
         self.consume(TokenType::Semicolon, "Expected ';' after return value.")?;
-        Ok(Stmt::Return(value))
+        Ok(Stmt::Return(keyword, value))
+    }
+
+    fn throw_statement(&mut self) -> Result<Stmt> {
+        let keyword = self.previous();
+        let value = self.expression()?;
+
+        self.consume(TokenType::Semicolon, "Expected ';' after thrown value.")?;
+        Ok(Stmt::Throw(keyword, value))
+    }
+
+    fn try_statement(&mut self) -> Result<Stmt> {
+        self.consume(TokenType::LeftBrace, "Expected '{' after 'try'.")?;
+        let body = self.statement_block("try")?;
+
+        let catch = if self.match_token(TokenType::Catch) {
+            let name = self.consume(TokenType::Identifier, "Expected error name after 'catch'.")?;
+            self.consume(TokenType::LeftBrace, "Expected '{' after error name.")?;
+            Some((name, self.statement_block("catch")?))
+        } else {
+            None
+        };
+
+        let finally = if self.match_token(TokenType::Finally) {
+            self.consume(TokenType::LeftBrace, "Expected '{' after 'finally'.")?;
+            Some(self.statement_block("finally")?)
+        } else {
+            None
+        };
+
+        if catch.is_none() && finally.is_none() {
+            return error!(
+                ReportKind::SyntaxError,
+                "Expected 'catch' or 'finally' after 'try' block.",
+                self.peek().location()
+            );
+        }
+
+        Ok(Stmt::Try(body, catch, finally))
+    }
+
+    fn yield_statement(&mut self) -> Result<Stmt> {
+        let keyword = self.previous();
+        match self.function_yields.last_mut() {
+            Some(yields) => *yields = true,
+            None => return error!(
+                ReportKind::SyntaxError,
+                "'yield' outside of a function.",
+                keyword.location()
+            ),
+        }
+
+        let value = if self.check(TokenType::Semicolon) {
+            Expr::Literal(Literal::Null)
+        } else {
+            self.expression()?
+        };
+
+        self.consume(TokenType::Semicolon, "Expected ';' after yielded value.")?;
+        Ok(Stmt::Yield(keyword, value))
+    }
+
+    /// Parses statements up to and including the closing brace of a block
+    /// whose opening brace was already consumed.
+    fn statement_block(&mut self, kind: &str) -> Result<Vec<Stmt>> {
+        let mut statements = vec![];
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            statements.push(self.declaration()?);
+        }
+
+        self.consume(TokenType::RightBrace, &format!("Expected '}}' after {kind} block."))?;
+        Ok(statements)
     }
 
     fn while_statement(&mut self) -> Result<Stmt> {
+        let keyword = self.previous();
         let condition = self.expression()?;
         self.consume(TokenType::LeftBrace, "Expected '{' after 'while'.")?;
 
@@ -204,22 +617,28 @@ impl Parser {
         }
 
         self.consume(TokenType::RightBrace, "Expected '}' after loop body.")?;
-        Ok(Stmt::While(condition, body))
+        Ok(Stmt::While(keyword, condition, body))
     }
 
     fn assignment(&mut self) -> Result<Expr> {
-        let lhs = self.conditional()?;
+        let lhs = self.pipeline()?;
 
         if self.match_token(TokenType::Equal) {
             let equals = self.previous();
             let value = self.assignment()?;
 
-            if let Expr::Variable(_, name) = lhs {
-                return Ok(Expr::Assignment(
-                    next_expr_id(),
-                    name,
-                    Box::new(value)
-                ));
+            match lhs {
+                Expr::Variable(_, name) => {
+                    return Ok(Expr::Assignment(
+                        next_id(),
+                        name,
+                        Box::new(value)
+                    ));
+                },
+                Expr::Get(object, op, name) if op.token_type() == TokenType::Dot => {
+                    return Ok(Expr::Set(object, name, Box::new(value)));
+                },
+                _ => {},
             }
 
             // Report error but don't return error
@@ -237,8 +656,27 @@ impl Parser {
         Ok(lhs)
     }
 
+    /// `x |> f(y)` calls `f(x, y)`, and `x |> f` calls `f(x)`.
+    fn pipeline(&mut self) -> Result<Expr> {
+        let mut expr = self.conditional()?;
+
+        while self.match_token(TokenType::PipeGreater) {
+            let operator = self.previous();
+            expr = match self.conditional()? {
+                Expr::Call(callee, paren, mut args) => {
+                    args.insert(0, Arg::Positional(expr));
+                    Expr::Call(callee, paren, args)
+                },
+                callee => Expr::Call(Box::new(callee), operator, vec![Arg::Positional(expr)]),
+            };
+        }
+
+        Ok(expr)
+    }
+
     fn conditional(&mut self) -> Result<Expr> {
         if self.match_token(TokenType::If) {
+            let keyword = self.previous();
             let condition = self.conditional()?;
             self.consume(TokenType::Then, "Expected 'then' after 'if' condition.")?;
 
@@ -250,13 +688,26 @@ impl Parser {
             };
 
             return Ok(Expr::If(
+                keyword,
                 Box::new(condition),
                 Box::new(then_branch),
                 Box::new(else_branch),
             ));
         }
 
-        self.logic()
+        self.coalesce()
+    }
+
+    fn coalesce(&mut self) -> Result<Expr> {
+        let mut expr = self.logic()?;
+
+        while self.match_token(TokenType::QuestionQuestion) {
+            let operator = self.previous();
+            let right = self.logic()?;
+            expr = Expr::Logical(Box::new(expr), operator, Box::new(right));
+        }
+
+        Ok(expr)
     }
 
     fn logic(&mut self) -> Result<Expr> {
@@ -336,10 +787,11 @@ impl Parser {
 
     fn block(&mut self) -> Result<Expr> {
         if self.match_token(TokenType::LeftBrace) {
+            let open = self.previous();
             let (statements, expr) = self.block_like()?;
 
-            self.consume(TokenType::RightBrace, "Expected '}' after block")?;
-            return Ok(Expr::Block(statements, expr));
+            let close = self.consume(TokenType::RightBrace, "Expected '}' after block")?;
+            return Ok(Expr::Block(open, statements, expr, close));
         }
 
         self.call()
@@ -350,7 +802,23 @@ impl Parser {
 
         loop {
             if self.match_token(TokenType::LeftParen) {
-                expr = self.finish_call(expr)?;
+                expr = self.finish_call(expr, None)?;
+            } else if self.match_token(TokenType::LeftBracket) {
+                let index = self.expression()?;
+                let bracket = self.consume(TokenType::RightBracket, "Expected ']' after index.")?;
+                expr = Expr::Index(Box::new(expr), bracket, Box::new(index));
+            } else if self.match_token(TokenType::Dot) {
+                let dot = self.previous();
+                let name = self.property_name("Expected property name after '.'.")?;
+                expr = Expr::Get(Box::new(expr), dot, name);
+            } else if self.match_token(TokenType::QuestionDot) {
+                let question_dot = self.previous();
+                if self.match_token(TokenType::LeftParen) {
+                    expr = self.finish_call(expr, Some(question_dot))?;
+                } else {
+                    let name = self.property_name("Expected property name after '?.'.")?;
+                    expr = Expr::Get(Box::new(expr), question_dot, name);
+                }
             } else {
                 break;
             }
@@ -359,22 +827,64 @@ impl Parser {
         Ok(expr)
     }
 
-    fn finish_call(&mut self, callee: Expr) -> Result<Expr> {
+    /// A property name, or a field index for tuples.
+    fn property_name(&mut self, message: &str) -> Result<Token> {
+        if self.match_any_token(vec![TokenType::Identifier, TokenType::Number]) {
+            return Ok(self.previous());
+        }
+
+        error!(ReportKind::SyntaxError, message, self.peek().location())
+    }
+
+    /// Optional calls (`f?.()`) are represented by a call whose token is the
+    /// `?.` instead of the closing parenthesis.
+    fn finish_call(&mut self, callee: Expr, question_dot: Option<Token>) -> Result<Expr> {
         let mut args = vec![];
 
         if !self.check(TokenType::RightParen) {
             loop {
-                args.push(self.expression()?);
+                if self.check(TokenType::Identifier) && self.check_next(TokenType::Colon) {
+                    let name = self.advance();
+                    self.advance();
+                    args.push(Arg::Named(name, self.expression()?));
+                } else if let Some(Arg::Named(_, _)) = args.last() {
+                    return error!(
+                        ReportKind::SyntaxError,
+                        "Positional arguments can't follow named arguments.",
+                        self.peek().location()
+                    );
+                } else {
+                    args.push(Arg::Positional(self.spreadable()?));
+                }
+
                 if !self.match_token(TokenType::Comma) { break; }
             }
         }
 
         let paren = self.consume(TokenType::RightParen, "Expected ')' after arguments.")?;
 
-        Ok(Expr::Call(Box::new(callee), paren, args))
+        Ok(Expr::Call(Box::new(callee), question_dot.unwrap_or(paren), args))
+    }
+
+    /// An expression that may be prefixed with `...` to spread it into the
+    /// surrounding list literal or argument list.
+    fn spreadable(&mut self) -> Result<Expr> {
+        if self.match_token(TokenType::DotDotDot) {
+            let dots = self.previous();
+            return Ok(Expr::Spread(dots, Box::new(self.expression()?)));
+        }
+
+        self.expression()
     }
 
     fn primary(&mut self) -> Result<Expr> {
+        if self.match_token(TokenType::Spawn) {
+            let keyword = self.previous();
+            let r#fn = self.consume(TokenType::Fn, "Expected 'fn' after 'spawn'.")?;
+            self.consume(TokenType::LeftParen, "Expected '(' after 'fn'.")?;
+            let function = self.function_body(r#fn, "fiber", None, vec![])?;
+            return Ok(Expr::Spawn(keyword, function));
+        }
         if self.match_token(TokenType::False) {
             return Ok(Expr::Literal(Literal::Bool(false)));
         }
@@ -391,15 +901,47 @@ impl Parser {
         }
 
         if self.match_token(TokenType::Identifier) {
-            return Ok(Expr::Variable(next_expr_id(), self.previous()));
+            return Ok(Expr::Variable(next_id(), self.previous()));
+        }
+
+        if self.match_token(TokenType::This) {
+            return Ok(Expr::This(next_id(), self.previous()));
         }
 
         if self.match_token(TokenType::LeftParen) {
+            if self.match_token(TokenType::RightParen) {
+                return Ok(Expr::Tuple(vec![]));
+            }
+
             let expr = self.expression()?;
+            if self.match_token(TokenType::Comma) {
+                let mut elements = vec![expr];
+                while !self.check(TokenType::RightParen) {
+                    elements.push(self.expression()?);
+                    if !self.match_token(TokenType::Comma) { break; }
+                }
+
+                self.consume(TokenType::RightParen, "Expected ')' after tuple elements.")?;
+                return Ok(Expr::Tuple(elements));
+            }
+
             self.consume(TokenType::RightParen, "Expected ')' after expression")?;
             return Ok(Expr::Grouping(Box::new(expr)));
         }
 
+        if self.match_token(TokenType::LeftBracket) {
+            let mut elements = vec![];
+            if !self.check(TokenType::RightBracket) {
+                loop {
+                    elements.push(self.spreadable()?);
+                    if !self.match_token(TokenType::Comma) { break; }
+                }
+            }
+
+            self.consume(TokenType::RightBracket, "Expected ']' after list elements.")?;
+            return Ok(Expr::List(elements));
+        }
+
         error!(
             ReportKind::SyntaxError,
             "Expected expression",
@@ -433,7 +975,7 @@ impl Parser {
 
         error!(
             ReportKind::SyntaxError,
-            &format!("{}", message),
+            message,
             self.peek().location()
         )
     }
@@ -442,6 +984,13 @@ impl Parser {
         self.peek().token_type() == token_type
     }
 
+    fn check_next(&self, token_type: TokenType) -> bool {
+        match self.tokens.get(self.current + 1) {
+            Some(token) => token.token_type() == token_type,
+            None => false,
+        }
+    }
+
     fn advance(&mut self) -> Token {
         if !self.is_at_end() {
             self.current += 1;
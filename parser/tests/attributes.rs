@@ -0,0 +1,51 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use common::stmt::Stmt;
+use common::token::Literal;
+use lexer::Lexer;
+use parser::Parser;
+
+fn parse(source: &str) -> common::errors::Result<Vec<Stmt>> {
+    let tokens = Lexer::new(source.to_string(), "<test>".to_string()).scan_tokens().to_vec();
+    Parser::new(tokens).parse()
+}
+
+#[test]
+fn attributes_attach_to_functions_and_classes() {
+    let statements = parse(r#"
+        /// Documented too.
+        @deprecated("use bar") @inline
+        fn foo() { 1 }
+
+        @test
+        class Suite {
+            @test fn works() {}
+        }
+    "#).unwrap();
+
+    let foo = &statements[0];
+    let names: Vec<&str> = foo.attributes().iter().map(|attribute| attribute.name()).collect();
+    assert_eq!(names, ["deprecated", "inline"]);
+    assert!(matches!(
+        foo.attribute("deprecated").unwrap().args.as_slice(),
+        [Literal::String(reason)] if reason == "use bar"
+    ));
+    assert!(matches!(foo, Stmt::Fn(function) if function.doc.is_some() && function.attribute("inline").is_some()));
+
+    match &statements[1] {
+        Stmt::Class(_, _, _, methods, _, _) => {
+            assert!(statements[1].attribute("test").is_some());
+            assert!(methods[0].attribute("test").is_some());
+        },
+        stmt => panic!("expected a class, got {stmt:?}"),
+    }
+
+    assert!(parse("@inline\nlet x = 1;").is_err());
+    assert!(parse("@deprecated(x)").is_err());
+}
@@ -0,0 +1,56 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::rc::Rc;
+
+use common::stmt::{Doc, Stmt};
+use lexer::Lexer;
+use parser::Parser;
+
+fn parse(source: &str) -> Vec<Stmt> {
+    let tokens = Lexer::new(source.to_string(), "<test>".to_string()).scan_tokens().to_vec();
+    Parser::new(tokens).parse().unwrap()
+}
+
+fn text(doc: &Option<Rc<Doc>>) -> Option<&str> {
+    doc.as_ref().map(|doc| doc.text.as_str())
+}
+
+#[test]
+fn doc_comments_attach_to_declarations() {
+    let statements = parse(r#"
+        /// Adds two numbers.
+        ///
+        /// Both have to be numbers.
+        fn add(a, b) { a + b }
+
+        /**
+         * A point.
+         */
+        class Point {
+            /// Makes a point.
+            fn init(x) { this.x = x; }
+        }
+
+        // Not documentation
+        let undocumented = 1;
+        /// The answer.
+        let answer = add(/// ignored
+            40, 2);
+    "#);
+
+    match &statements[..] {
+        [Stmt::Fn(add), Stmt::Class(_, _, _, methods, class_doc, _), Stmt::Let(_, _, _, None), Stmt::Let(_, _, _, let_doc)] => {
+            assert_eq!(text(&add.doc), Some("Adds two numbers.\n\nBoth have to be numbers."));
+            assert_eq!(text(class_doc), Some("A point."));
+            assert_eq!(text(&methods[0].doc), Some("Makes a point."));
+            assert_eq!(text(let_doc), Some("The answer."));
+        },
+        statements => panic!("unexpected statements: {statements:#?}"),
+    }
+}
@@ -1,172 +1,896 @@
-use std::{collections::HashMap, mem};
+mod lint;
+pub mod symbols;
 
-use common::{stmt::Stmt, errors::{Result, ReportKind}, expr::Expr, token::Token, error};
-use interpreter::Interpreter;
+use std::{collections::{HashMap, HashSet}, mem, rc::Rc};
+
+use common::{
+    stmt::{Attribute, Function, Stmt},
+    errors::{report_error, FluetError, Result, ReportKind},
+    expr::Expr,
+    location::Location,
+    pattern::Pattern,
+    token::{Literal, Token, TokenType},
+};
+use interpreter::{env::Local, Interpreter};
+
+pub use lint::Lint;
+use symbols::{Span, SymbolIndex, SymbolKind};
+
+/// A local variable's slot in its scope, and whether its initializer has been
+/// resolved yet.
+struct Variable {
+    slot: usize,
+    defined: bool,
+    /// The `@deprecated` attribute of the function or class declared here.
+    deprecated: Option<Attribute>,
+    binding: Binding,
+    /// Where the variable is declared, which `this` isn't.
+    location: Option<Location>,
+    used: bool,
+    /// Its index in the symbol index, which `this` doesn't have.
+    symbol: Option<usize>,
+}
+
+/// What declared a variable, for the lints about it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Binding {
+    /// A `let` or `for` variable.
+    Let,
+    Param,
+    /// A function, class, interface or caught error.
+    Other,
+}
+
+impl Binding {
+    /// The kind of symbol a pattern binding this way declares.
+    fn symbol_kind(self) -> SymbolKind {
+        match self {
+            Binding::Param => SymbolKind::Parameter,
+            _ => SymbolKind::Variable,
+        }
+    }
+}
+
+struct Scope {
+    variables: HashMap<String, Variable>,
+    /// The number of slots handed out, which also counts shadowed variables.
+    slots: usize,
+    /// Its index in the symbol index.
+    index: usize,
+}
+
+impl Scope {
+    /// Declares a variable, returning `false` if the scope already has one
+    /// with the same name.
+    fn declare(&mut self, name: &str, binding: Binding, location: Option<Location>, symbol: Option<usize>) -> bool {
+        let variable = Variable {
+            slot: self.slots,
+            defined: false,
+            deprecated: None,
+            binding,
+            location,
+            used: false,
+            symbol,
+        };
+        self.slots += 1;
+        self.variables.insert(name.to_string(), variable).is_none()
+    }
+
+    fn define(&mut self, name: &str) {
+        if let Some(variable) = self.variables.get_mut(name) {
+            variable.defined = true;
+        }
+    }
+
+    fn is_being_defined(&self, name: &str) -> bool {
+        matches!(self.variables.get(name), Some(Variable { defined: false, .. }))
+    }
+}
+
+/// A function or class being resolved. Its code can only use the local
+/// variables around it by capturing them.
+struct Closure {
+    /// The index in `scopes` of its outermost scope.
+    base: usize,
+    /// Where each captured variable is, from the scope the closure is
+    /// created in.
+    captures: Vec<Local>,
+    /// The index in `captures` of each captured variable, by the index of
+    /// its scope and its slot.
+    indices: HashMap<(usize, usize), usize>,
+}
 
 pub struct Resolver {
-    scopes: Vec<HashMap<String, bool>>,
-    locals: HashMap<usize, usize>,
+    scopes: Vec<Scope>,
+    /// The functions and classes being resolved, innermost last.
+    closures: Vec<Closure>,
+    /// Where each local variable is, by expression id.
+    locals: HashMap<usize, Local>,
+    /// The variables each function and class captures, by id.
+    captures: HashMap<usize, Vec<Local>>,
+    /// The names declared in the global scope so far, by the resolved code or
+    /// before it.
+    globals: HashSet<String>,
+    /// The globals that exist before the resolved code runs, like native
+    /// functions.
+    defined_globals: HashSet<String>,
+    in_class: bool,
+    in_function: bool,
+    /// The `@deprecated` attributes of global functions and classes, by name.
+    deprecated_globals: HashMap<String, Attribute>,
+    /// The `@deprecated` attributes of methods by name, or `None` for names
+    /// that some class declares a method with without deprecating it.
+    deprecated_methods: HashMap<String, Option<Attribute>>,
+    /// The lints that are turned off.
+    allowed: HashSet<Lint>,
+    symbols: SymbolIndex,
+    /// The symbol of the latest declaration of each global.
+    global_symbols: HashMap<String, usize>,
+    /// References to globals that weren't declared yet, and whether they are
+    /// assignments.
+    forward_references: Vec<(Token, bool)>,
+    errors: Vec<FluetError>,
+    warnings: Vec<FluetError>,
 }
 
 impl Resolver {
     pub fn new() -> Self {
         Self {
             scopes: Vec::new(),
+            closures: Vec::new(),
             locals: HashMap::new(),
+            captures: HashMap::new(),
+            globals: HashSet::new(),
+            defined_globals: HashSet::new(),
+            in_class: false,
+            in_function: false,
+            deprecated_globals: HashMap::new(),
+            deprecated_methods: HashMap::new(),
+            allowed: HashSet::new(),
+            symbols: SymbolIndex::default(),
+            global_symbols: HashMap::new(),
+            forward_references: vec![],
+            errors: vec![],
+            warnings: vec![],
         }
     }
 
+    /// Resolves top-level code, reporting every undefined name, duplicate
+    /// declaration and misplaced `return` in it at once.
     pub fn resolve(&mut self,
         stmts: &Vec<Stmt>,
         interpreter: &mut Interpreter,
     ) -> Result<()>{
-        self.resolve_stmts(stmts)?;
+        // Globals can be used before they are declared, from functions
+        self.defined_globals.extend(interpreter.global_names());
+        self.globals.extend(self.defined_globals.iter().cloned());
+        for stmt in stmts {
+            self.declare_global(stmt);
+        }
+
+        self.resolve_stmts(stmts);
+        self.link_forward_references();
         interpreter.extend_locals(mem::take(&mut self.locals));
-        Ok(())
+        interpreter.extend_captures(mem::take(&mut self.captures));
+
+        match self.errors.is_empty() {
+            true => Ok(()),
+            false => Err(FluetError::combine(mem::take(&mut self.errors))),
+        }
     }
 
-    pub fn resolve_stmts(&mut self, stmts: &Vec<Stmt>) -> Result<()> {
+    /// Takes the warnings found while resolving, like calls to deprecated
+    /// functions and lints.
+    pub fn take_warnings(&mut self) -> Vec<FluetError> {
+        mem::take(&mut self.warnings)
+    }
+
+    /// The declarations, references and scopes in the code resolved so far.
+    pub fn symbols(&self) -> &SymbolIndex {
+        &self.symbols
+    }
+
+    /// Turns a lint off. Every lint is on by default.
+    pub fn allow(&mut self, lint: Lint) {
+        self.allowed.insert(lint);
+    }
+
+    fn resolve_stmts(&mut self, stmts: &Vec<Stmt>) {
         for stmt in stmts {
-            self.resolve_stmt(stmt)?;
+            self.resolve_stmt(stmt);
         }
 
-        Ok(())
+        let end = stmts.iter().position(|stmt| matches!(stmt, Stmt::Return(..) | Stmt::Throw(..)));
+        if let Some(end) = end.filter(|&end| end + 1 < stmts.len()) {
+            self.lint_unreachable(&stmts[end]);
+        }
     }
 
-    fn resolve_stmt(&mut self, stmt: &Stmt) -> Result<()> {
+    /// Resolves the statements and trailing expression of a block or function
+    /// body.
+    fn resolve_body(&mut self, stmts: &Vec<Stmt>, expr: &Expr) {
+        self.resolve_stmts(stmts);
+        self.resolve_expr(expr);
+
+        // A missing trailing expression is parsed as `null`
+        if let Some(last @ (Stmt::Return(..) | Stmt::Throw(..))) = stmts.last() {
+            if !matches!(expr, Expr::Literal(Literal::Null)) {
+                self.lint_unreachable(last);
+            }
+        }
+    }
+
+    fn resolve_stmt(&mut self, stmt: &Stmt) {
         match stmt {
-            Stmt::Expr(expr) => self.resolve_expr(expr),
-            Stmt::Fn(name, args, body, ret) => {
-                self.declare(&name);
-                self.define(&name);
+            Stmt::Class(id, name, interfaces, methods, _, attributes) => {
+                for interface in interfaces {
+                    self.resolve_expr(interface);
+                }
+
+                self.declare(name, Binding::Other, SymbolKind::Class);
+                self.define(name);
+                self.deprecate(name, attributes);
+                self.deprecate_methods(methods);
+                self.check_unique(methods.iter().map(|method| &method.name), "Method", name);
 
+                self.begin_closure();
                 self.begin_scope();
-                for arg in args {
-                    self.declare(arg);
-                    self.define(arg);
+                self.visit(name);
+                let scope = self.scopes.last_mut().unwrap();
+                scope.declare("this", Binding::Other, None, None);
+                scope.define("this");
+                let in_class = mem::replace(&mut self.in_class, true);
+
+                for method in methods {
+                    let scope = self.scopes.last().map(|scope| scope.index);
+                    self.symbols.declare(&method.name, SymbolKind::Method, scope);
+                    self.resolve_function(method);
                 }
 
-                self.resolve_stmts(body)?;
-                self.resolve_expr(ret)?;
-                Ok(())
+                self.in_class = in_class;
+                self.end_scope();
+                self.end_closure(*id);
             },
-            Stmt::Let(name, expr) => {
-                self.declare(&name);
-                self.resolve_expr(expr)?;
-                self.define(&name);
-                Ok(())
+            Stmt::Expr(expr) => self.resolve_expr(expr),
+            Stmt::Fn(function) => {
+                self.declare(&function.name, Binding::Other, SymbolKind::Function);
+                self.define(&function.name);
+                self.deprecate(&function.name, &function.attributes);
+                self.resolve_function(function);
+            },
+            Stmt::For(pattern, iterable, body) => {
+                self.resolve_expr(iterable);
+                self.begin_scope();
+                self.declare_pattern(pattern, Binding::Let);
+                self.define_pattern(pattern);
+                self.resolve_stmts(body);
+                self.end_scope();
+            },
+            Stmt::Let(pattern, _, expr, _) => {
+                self.declare_pattern(pattern, Binding::Let);
+                self.resolve_expr(expr);
+                self.define_pattern(pattern);
+            },
+            Stmt::Interface(name, methods, _) => {
+                self.declare(name, Binding::Other, SymbolKind::Interface);
+                self.define(name);
+                self.check_unique(methods.iter().map(|method| &method.name), "Method", name);
             },
             Stmt::Loop(body) => {
                 self.begin_scope();
-                self.resolve_stmts(body)?;
+                self.resolve_stmts(body);
+                self.end_scope();
+            },
+            Stmt::Return(keyword, expr) => {
+                self.visit(keyword);
+                if !self.in_function {
+                    self.error("Cannot return from top-level code.", keyword.location());
+                }
+
+                self.resolve_expr(expr);
+            },
+            Stmt::Throw(keyword, expr) => {
+                self.visit(keyword);
+                self.resolve_expr(expr);
+            },
+            Stmt::Try(body, catch, finally) => {
+                self.begin_scope();
+                self.resolve_stmts(body);
                 self.end_scope();
-                Ok(())
+
+                if let Some((name, handler)) = catch {
+                    self.begin_scope();
+                    self.declare(name, Binding::Other, SymbolKind::Variable);
+                    self.define(name);
+                    self.resolve_stmts(handler);
+                    self.end_scope();
+                }
+
+                if let Some(finally) = finally {
+                    self.begin_scope();
+                    self.resolve_stmts(finally);
+                    self.end_scope();
+                }
             },
-            Stmt::Return(expr) => self.resolve_expr(expr),
-            Stmt::While(cond, body) => {
-                self.resolve_expr(cond)?;
+            Stmt::Yield(keyword, expr) => {
+                self.visit(keyword);
+                self.resolve_expr(expr);
+            },
+            Stmt::While(keyword, cond, body) => {
+                self.visit(keyword);
+                self.lint_condition(keyword, cond);
+                self.resolve_expr(cond);
                 self.begin_scope();
-                self.resolve_stmts(body)?;
+                self.resolve_stmts(body);
                 self.end_scope();
-                Ok(())
             },
         }
     }
 
-    fn resolve_expr(&mut self, expr: &Expr) -> Result<()> {
+    fn resolve_expr(&mut self, expr: &Expr) {
         match expr {
             Expr::Assignment(expr_id, name, value) => {
-                self.resolve_expr(value)?;
-                println!("LOOOOL");
-                self.resolve_local(*expr_id, name);
-                Ok(())
+                self.resolve_expr(value);
+                self.check_initialized(name);
+                self.resolve_local(*expr_id, name, true);
             },
-            Expr::Binary(lhs, _, rhs) => {
-                self.resolve_expr(lhs)?;
-                self.resolve_expr(rhs)?;
-                Ok(())
+            Expr::Binary(lhs, operator, rhs) => {
+                self.visit(operator);
+                self.lint_nan_comparison(lhs, operator, rhs);
+                self.resolve_expr(lhs);
+                self.resolve_expr(rhs);
             },
-            Expr::Block(stmts, expr) => {
+            Expr::Block(open, stmts, expr, close) => {
                 self.begin_scope();
-                self.resolve_expr(expr)?;
-                self.resolve_stmts(stmts)?;
+                self.visit(open);
+                self.resolve_body(stmts, expr);
+                self.visit(close);
                 self.end_scope();
-                Ok(())
             },
-            Expr::Call(callee, _, args) => {
-                self.resolve_expr(callee)?;
-                for arg in args {
-                    self.resolve_expr(arg)?;
+            Expr::Call(callee, paren, args) => {
+                self.visit(paren);
+                match &**callee {
+                    Expr::Variable(_, name) => self.warn_if_deprecated(name),
+                    Expr::Get(_, _, name) => self.warn_if_deprecated_method(name),
+                    _ => {},
                 }
 
-                Ok(())
+                self.resolve_expr(callee);
+                for arg in args {
+                    self.resolve_expr(arg.expr());
+                }
+            },
+            Expr::Get(object, _, name) => {
+                self.visit(name);
+                self.resolve_expr(object);
             },
             Expr::Grouping(expr) => self.resolve_expr(expr),
-            Expr::If(cond, then_branch, else_branch) => {
-                self.resolve_expr(cond)?;
-                self.resolve_expr(then_branch)?;
-                self.resolve_expr(else_branch)?;
-                Ok(())
-            },
-            Expr::Literal(_) => Ok(()),
-            Expr::Logical(lhs, _, rhs) => {
-                self.resolve_expr(lhs)?;
-                self.resolve_expr(rhs)?;
-                Ok(())
-            },
-            Expr::Unary(_, expr) => self.resolve_expr(expr),
-            Expr::Variable(expr_id, name) => {
-                if !self.scopes.is_empty() && self.scopes.last().unwrap().contains_key(name.lexeme()) {
-                    return error!(
-                        ReportKind::SyntaxError,
-                        &format!("Cannot read local variable {} in its own initializer.", name.lexeme()),
-                        name.location()
-                    );
+            Expr::If(keyword, cond, then_branch, else_branch) => {
+                self.visit(keyword);
+                self.lint_condition(keyword, cond);
+                self.resolve_expr(cond);
+                self.resolve_expr(then_branch);
+                self.resolve_expr(else_branch);
+            },
+            Expr::Index(object, bracket, index) => {
+                self.visit(bracket);
+                self.resolve_expr(object);
+                self.resolve_expr(index);
+            },
+            Expr::List(elements) => {
+                for element in elements {
+                    self.resolve_expr(element);
+                }
+            },
+            Expr::Literal(_) => {},
+            Expr::Logical(lhs, operator, rhs) => {
+                self.visit(operator);
+                self.resolve_expr(lhs);
+                self.resolve_expr(rhs);
+            },
+            Expr::Set(object, name, value) => {
+                self.visit(name);
+                self.resolve_expr(value);
+                self.resolve_expr(object);
+            },
+            Expr::Spawn(keyword, function) => {
+                self.visit(keyword);
+                self.resolve_function(function);
+            },
+            Expr::Spread(dots, expr) => {
+                self.visit(dots);
+                self.resolve_expr(expr);
+            },
+            Expr::Tuple(elements) => {
+                for element in elements {
+                    self.resolve_expr(element);
+                }
+            },
+            Expr::This(expr_id, keyword) => {
+                if !self.in_class {
+                    self.error("Cannot use 'this' outside of a class.", keyword.location());
+                    return;
                 }
 
-                println!("LMAO");
-                self.resolve_local(*expr_id, name);
-                Ok(())
+                self.resolve_local(*expr_id, keyword, false);
             },
+            Expr::Unary(operator, expr) => {
+                self.visit(operator);
+                self.resolve_expr(expr);
+            },
+            Expr::Variable(expr_id, name) => {
+                self.check_initialized(name);
+                self.resolve_local(*expr_id, name, false);
+            },
+        }
+    }
+
+    fn resolve_function(&mut self, function: &Function) {
+        self.begin_closure();
+        self.begin_scope();
+        self.visit(&function.name);
+        let in_function = mem::replace(&mut self.in_function, true);
+
+        for param in &function.params {
+            if let Some(default) = &param.default {
+                self.resolve_expr(default);
+            }
+
+            self.declare_pattern(&param.pattern, Binding::Param);
+            self.define_pattern(&param.pattern);
+        }
+
+        let (open, close) = &function.braces;
+        self.visit(open);
+        self.resolve_body(&function.body, &function.ret);
+        self.visit(close);
+
+        self.in_function = in_function;
+        self.end_scope();
+        self.end_closure(function.id);
+    }
+
+    fn check_initialized(&mut self, name: &Token) {
+        if matches!(self.scopes.last(), Some(scope) if scope.is_being_defined(name.lexeme())) {
+            self.error(
+                &format!("Cannot use local variable {} in its own initializer.", name.lexeme()),
+                name.location()
+            );
         }
     }
 
-    fn resolve_local(&mut self, expr_id: usize, name: &Token) {
-        println!("Resolving local {} id: {}", name.lexeme(), expr_id);
-        println!("Scopes: {:#?}", self.scopes);
-        for (i, scope) in self.scopes.iter().rev().enumerate() {
-            println!("Resolving local {} id: {}", name.lexeme(), expr_id);
-            if scope.contains_key(name.lexeme()) {
-                println!("Resolving local {} id: {}", name.lexeme(), expr_id);
-                self.locals.insert(expr_id, self.scopes.len() - 1 - i);
+    /// Reports methods of a class or interface that share a name.
+    fn check_unique<'a>(&mut self, names: impl Iterator<Item = &'a Token>, kind: &str, owner: &Token) {
+        let mut seen = HashSet::new();
+        for name in names {
+            if !seen.insert(name.lexeme()) {
+                self.error(
+                    &format!("{kind} {} is already declared in {}.", name.lexeme(), owner.lexeme()),
+                    name.location()
+                );
+            }
+        }
+    }
+
+    /// Records where the local variable `name` is, capturing it if it belongs
+    /// to an enclosing function. Names that aren't found have to be globals.
+    fn resolve_local(&mut self, expr_id: usize, name: &Token, write: bool) {
+        self.visit(name);
+
+        for index in (0..self.scopes.len()).rev() {
+            if let Some(variable) = self.scopes[index].variables.get_mut(name.lexeme()) {
+                variable.used = true;
+                let (slot, symbol) = (variable.slot, variable.symbol);
+                let local = self.access(self.closures.len(), self.scopes.len() - 1, index, slot);
+                self.locals.insert(expr_id, local);
+                if let Some(symbol) = symbol {
+                    self.symbols.refer(Span::of(name), symbol, write);
+                }
                 return;
             }
         }
+
+        if let Some(&symbol) = self.global_symbols.get(name.lexeme()) {
+            self.symbols.refer(Span::of(name), symbol, write);
+        } else if self.globals.contains(name.lexeme()) {
+            // Top-level code runs in order, so only functions can be called
+            // after the globals they use are declared
+            if self.closures.is_empty() && !self.defined_globals.contains(name.lexeme()) {
+                self.error(&format!("{} is used before it is declared.", name.lexeme()), name.location());
+            } else {
+                self.forward_references.push((name.clone(), write));
+            }
+        } else {
+            self.error(&format!("{} is not defined.", name.lexeme()), name.location());
+        }
     }
 
-    fn declare(&mut self, name: &Token) {
-        if self.scopes.is_empty() {
-            return;
+    /// Links references to globals that were made before the globals were
+    /// declared, like from functions, to their first declaration.
+    fn link_forward_references(&mut self) {
+        for (name, write) in mem::take(&mut self.forward_references) {
+            let first = self.symbols.symbols().iter().position(|symbol| {
+                symbol.scope.is_none() && symbol.name == name.lexeme()
+            });
+
+            // Native functions have no declaration
+            if let Some(symbol) = first {
+                self.symbols.refer(Span::of(&name), symbol, write);
+            }
         }
+    }
 
-        let scope = self.scopes.last_mut().unwrap();
-        scope.insert(name.lexeme().to_string(), false);
+    /// Remembers the names a top-level statement declares, so that code
+    /// before it can refer to them, and whether they are deprecated.
+    fn declare_global(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Class(_, name, _, methods, _, attributes) => {
+                self.declare_deprecated_global(name, attributes);
+                self.deprecate_methods(methods);
+            },
+            Stmt::Interface(name, ..) => {
+                self.globals.insert(name.lexeme().to_string());
+            },
+            Stmt::Fn(function) => self.declare_deprecated_global(&function.name, &function.attributes),
+            Stmt::Let(pattern, ..) => self.globals.extend(pattern_names(pattern)),
+            _ => {},
+        }
+    }
+
+    /// Declares a global function or class ahead of resolving, deprecating it
+    /// for the code before it too if its first declaration is deprecated.
+    fn declare_deprecated_global(&mut self, name: &Token, attributes: &[Attribute]) {
+        if self.globals.insert(name.lexeme().to_string()) {
+            self.deprecate(name, attributes);
+        }
+    }
+
+    /// Remembers which of a class's methods are deprecated. Calls can't tell
+    /// the classes apart, so a name is only deprecated while every class
+    /// that declares it deprecates it.
+    fn deprecate_methods(&mut self, methods: &[Rc<Function>]) {
+        for method in methods {
+            let attribute = method.attribute("deprecated").cloned();
+            let deprecated = self.deprecated_methods.entry(method.name.lexeme().to_string()).or_insert(attribute.clone());
+            if attribute.is_none() {
+                *deprecated = None;
+            }
+        }
+    }
+
+    /// Remembers that the function or class just declared as `name` is
+    /// deprecated, if it has a `@deprecated` attribute.
+    fn deprecate(&mut self, name: &Token, attributes: &[Attribute]) {
+        let attribute = match attributes.iter().find(|attribute| attribute.name() == "deprecated") {
+            Some(attribute) => attribute.clone(),
+            None => return,
+        };
+
+        match self.scopes.last_mut() {
+            Some(scope) => {
+                if let Some(variable) = scope.variables.get_mut(name.lexeme()) {
+                    variable.deprecated = Some(attribute);
+                }
+            },
+            None => {
+                self.deprecated_globals.insert(name.lexeme().to_string(), attribute);
+            },
+        }
+    }
+
+    fn warn_if_deprecated(&mut self, name: &Token) {
+        let deprecated = match self.scopes.iter().rev().find_map(|scope| scope.variables.get(name.lexeme())) {
+            Some(variable) => variable.deprecated.as_ref(),
+            None => self.deprecated_globals.get(name.lexeme()),
+        };
+
+        if let Some(attribute) = deprecated.cloned() {
+            self.warn_deprecated(name, &attribute);
+        }
+    }
+
+    fn warn_if_deprecated_method(&mut self, name: &Token) {
+        if let Some(Some(attribute)) = self.deprecated_methods.get(name.lexeme()).cloned() {
+            self.warn_deprecated(name, &attribute);
+        }
+    }
+
+    fn warn_deprecated(&mut self, name: &Token, attribute: &Attribute) {
+        let message = match attribute.args.first() {
+            Some(Literal::String(reason)) => format!("{} is deprecated: {reason}", name.lexeme()),
+            _ => format!("{} is deprecated.", name.lexeme()),
+        };
+
+        self.lint(Lint::Deprecated, &message, name.location());
+    }
+
+    fn declare_pattern(&mut self, pattern: &Pattern, binding: Binding) {
+        match pattern {
+            Pattern::List(_, elements, rest) => {
+                for (pattern, _) in elements {
+                    self.declare_pattern(pattern, binding);
+                }
+
+                if let Some(rest) = rest {
+                    self.declare(rest, binding, binding.symbol_kind());
+                }
+            },
+            Pattern::Name(name) => self.declare(name, binding, binding.symbol_kind()),
+            Pattern::Object(_, fields) => {
+                for (_, pattern, _) in fields {
+                    self.declare_pattern(pattern, binding);
+                }
+            },
+        }
+    }
+
+    /// Resolves the default values in a pattern and defines the names it
+    /// binds, in the order they are bound at runtime.
+    fn define_pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::List(_, elements, rest) => {
+                for (pattern, default) in elements {
+                    if let Some(default) = default {
+                        self.resolve_expr(default);
+                    }
+
+                    self.define_pattern(pattern);
+                }
+
+                if let Some(rest) = rest {
+                    self.define(rest);
+                }
+            },
+            Pattern::Name(name) => self.define(name),
+            Pattern::Object(_, fields) => {
+                for (_, pattern, default) in fields {
+                    if let Some(default) = default {
+                        self.resolve_expr(default);
+                    }
+
+                    self.define_pattern(pattern);
+                }
+            },
+        }
+    }
+
+    fn declare(&mut self, name: &Token, binding: Binding, kind: SymbolKind) {
+        let shadows = match self.scopes.split_last() {
+            Some((_, enclosing)) => enclosing.iter().any(|scope| scope.variables.contains_key(name.lexeme())),
+            None => false,
+        };
+        if shadows && !name.lexeme().starts_with('_') {
+            self.lint(
+                Lint::Shadowing,
+                &format!("{} shadows a variable in an enclosing scope.", name.lexeme()),
+                name.location()
+            );
+        }
+
+        let symbol = self.symbols.declare(name, kind, self.scopes.last().map(|scope| scope.index));
+        self.visit(name);
+
+        match self.scopes.last_mut() {
+            Some(scope) => {
+                if !scope.declare(name.lexeme(), binding, Some(name.location().clone()), Some(symbol)) {
+                    self.error(
+                        &format!("{} is already declared in this scope.", name.lexeme()),
+                        name.location()
+                    );
+                }
+            },
+            // Declaring a global again replaces it, like a line in the REPL
+            // does, so it isn't an error. It's no longer the deprecated one.
+            None => {
+                self.deprecated_globals.remove(name.lexeme());
+                self.global_symbols.insert(name.lexeme().to_string(), symbol);
+            },
+        }
     }
 
     fn define(&mut self, name: &Token) {
-        if self.scopes.is_empty() {
-            return;
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.define(name.lexeme());
+        }
+    }
+
+    fn error(&mut self, message: &str, location: &Location) {
+        self.errors.push(report_error(ReportKind::SyntaxError, None, message, location));
+    }
+
+    fn lint(&mut self, lint: Lint, message: &str, location: &Location) {
+        if !self.allowed.contains(&lint) {
+            let warning = report_error(ReportKind::Warning, Some(lint.name()), message, location);
+            self.warnings.push(warning);
+        }
+    }
+
+    fn lint_unreachable(&mut self, end: &Stmt) {
+        let (keyword, what) = match end {
+            Stmt::Return(keyword, _) => (keyword, "return"),
+            Stmt::Throw(keyword, _) => (keyword, "throw"),
+            _ => return,
+        };
+
+        self.lint(
+            Lint::UnreachableCode,
+            &format!("Code after this {what} is unreachable."),
+            keyword.location()
+        );
+    }
+
+    /// Warns about `if` and `while` conditions that are literals.
+    fn lint_condition(&mut self, keyword: &Token, cond: &Expr) {
+        let mut cond = cond;
+        while let Expr::Grouping(expr) = cond {
+            cond = expr;
+        }
+
+        let value = match cond {
+            Expr::Literal(Literal::Bool(value)) => *value,
+            Expr::Literal(Literal::Null) => false,
+            _ => return,
+        };
+
+        let hint = match (keyword.token_type(), value) {
+            (TokenType::While, true) => ", use 'loop' instead",
+            _ => "",
+        };
+        self.lint(
+            Lint::ConstantCondition,
+            &format!("This condition is always {value}{hint}."),
+            keyword.location()
+        );
+    }
+
+    /// Warns about comparisons where one side is a constant NaN, which are
+    /// never true (or always true for `!=`).
+    fn lint_nan_comparison(&mut self, lhs: &Expr, operator: &Token, rhs: &Expr) {
+        let comparison = matches!(
+            operator.token_type(),
+            TokenType::BangEqual
+                | TokenType::EqualEqual
+                | TokenType::Greater
+                | TokenType::GreaterEqual
+                | TokenType::Less
+                | TokenType::LessEqual
+        );
+
+        let is_nan = |expr| constant_number(expr).is_some_and(f64::is_nan);
+        if comparison && (is_nan(lhs) || is_nan(rhs)) {
+            let result = match operator.token_type() {
+                TokenType::BangEqual => "true",
+                _ => "false",
+            };
+
+            self.lint(
+                Lint::NanComparison,
+                &format!("Comparing with NaN is always {result}."),
+                operator.location()
+            );
+        }
+    }
+
+    /// Finds the variable in slot `slot` of scope `scope` from code in the
+    /// first `level` closures, whose innermost scope is `innermost`. Variables
+    /// outside of the innermost of those closures are captured by it, and by
+    /// every closure between it and the variable.
+    fn access(&mut self, level: usize, innermost: usize, scope: usize, slot: usize) -> Local {
+        let closure = match level.checked_sub(1) {
+            Some(closure) if scope < self.closures[closure].base => closure,
+            _ => return Local::Slot(innermost - scope, slot),
+        };
+
+        if let Some(&index) = self.closures[closure].indices.get(&(scope, slot)) {
+            return Local::Upvalue(index);
         }
 
-        let scope = self.scopes.last_mut().unwrap();
-        scope.insert(name.lexeme().to_string(), true);
+        // It's captured from the scope just outside the closure
+        let capture = self.access(closure, self.closures[closure].base - 1, scope, slot);
+        let closure = &mut self.closures[closure];
+        closure.captures.push(capture);
+        closure.indices.insert((scope, slot), closure.captures.len() - 1);
+        Local::Upvalue(closure.captures.len() - 1)
+    }
+
+    fn begin_closure(&mut self) {
+        self.closures.push(Closure {
+            base: self.scopes.len(),
+            captures: vec![],
+            indices: HashMap::new(),
+        });
+    }
+
+    fn end_closure(&mut self, id: usize) {
+        if let Some(closure) = self.closures.pop() {
+            if !closure.captures.is_empty() {
+                self.captures.insert(id, closure.captures);
+            }
+        }
+    }
+
+    /// Widens the innermost scope to cover a token in it.
+    fn visit(&mut self, token: &Token) {
+        if let Some(scope) = self.scopes.last() {
+            self.symbols.extend_scope(scope.index, Span::of(token));
+        }
     }
 
     fn begin_scope(&mut self) {
-        self.scopes.push(HashMap::new());
+        let parent = self.scopes.last().map(|scope| scope.index);
+        self.scopes.push(Scope {
+            variables: HashMap::new(),
+            slots: 0,
+            index: self.symbols.begin_scope(parent),
+        });
     }
 
     fn end_scope(&mut self) {
-        self.scopes.pop();
+        let scope = match self.scopes.pop() {
+            Some(scope) => scope,
+            None => return,
+        };
+
+        let mut unused: Vec<(&String, &Variable)> = scope.variables
+            .iter()
+            .filter(|(name, variable)| {
+                !variable.used && variable.binding != Binding::Other && !name.starts_with('_')
+            })
+            .collect();
+        unused.sort_by_key(|(_, variable)| variable.slot);
+
+        for (name, variable) in unused {
+            let what = match variable.binding {
+                Binding::Param => "parameter",
+                _ => "variable",
+            };
+
+            if let Some(location) = &variable.location {
+                self.lint(Lint::UnusedVariable, &format!("Unused {what} {name}."), location);
+            }
+        }
+    }
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The names a pattern binds.
+fn pattern_names(pattern: &Pattern) -> Vec<String> {
+    match pattern {
+        Pattern::List(_, elements, rest) => elements
+            .iter()
+            .flat_map(|(pattern, _)| pattern_names(pattern))
+            .chain(rest.iter().map(|rest| rest.lexeme().to_string()))
+            .collect(),
+        Pattern::Name(name) => vec![name.lexeme().to_string()],
+        Pattern::Object(_, fields) => fields
+            .iter()
+            .flat_map(|(_, pattern, _)| pattern_names(pattern))
+            .collect(),
+    }
+}
+
+/// The value of an expression made only of number literals and arithmetic.
+fn constant_number(expr: &Expr) -> Option<f64> {
+    match expr {
+        Expr::Binary(lhs, operator, rhs) => {
+            let (lhs, rhs) = (constant_number(lhs)?, constant_number(rhs)?);
+            match operator.token_type() {
+                TokenType::Minus => Some(lhs - rhs),
+                TokenType::Percent => Some(lhs % rhs),
+                TokenType::Plus => Some(lhs + rhs),
+                TokenType::Slash => Some(lhs / rhs),
+                TokenType::Star => Some(lhs * rhs),
+                _ => None,
+            }
+        },
+        Expr::Grouping(expr) => constant_number(expr),
+        Expr::Literal(Literal::Number(number)) => Some(*number),
+        Expr::Unary(operator, expr) if operator.token_type() == TokenType::Minus => Some(-constant_number(expr)?),
+        _ => None,
     }
 }
@@ -0,0 +1,62 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+
+/// A kind of warning the resolver can give, which can be turned off on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// An `if` or `while` condition that is a literal.
+    ConstantCondition,
+    /// A call to a function, class or method with a `@deprecated` attribute.
+    Deprecated,
+    /// A comparison with a constant that is NaN, like `0 / 0`, which is
+    /// never equal to anything.
+    NanComparison,
+    /// A local variable declared with the same name as one in an enclosing
+    /// scope.
+    Shadowing,
+    /// Statements after a `return` or `throw`.
+    UnreachableCode,
+    /// A local variable or parameter that is never used. Names starting with
+    /// `_` are exempt.
+    UnusedVariable,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 6] = [
+        Lint::ConstantCondition,
+        Lint::Deprecated,
+        Lint::NanComparison,
+        Lint::Shadowing,
+        Lint::UnreachableCode,
+        Lint::UnusedVariable,
+    ];
+
+    /// The name the lint is reported and configured with.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::ConstantCondition => "constant_condition",
+            Lint::Deprecated => "deprecated",
+            Lint::NanComparison => "nan_comparison",
+            Lint::Shadowing => "shadowing",
+            Lint::UnreachableCode => "unreachable_code",
+            Lint::UnusedVariable => "unused_variable",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
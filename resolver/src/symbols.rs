@@ -0,0 +1,177 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use common::token::Token;
+
+/// A range of source, from the 1-based row and column of its first character
+/// to those of its last one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+}
+
+impl Span {
+    pub fn of(token: &Token) -> Self {
+        // Token locations point at their last character
+        let location = token.location();
+        let start = (location.column + 1).saturating_sub(token.lexeme().chars().count());
+        Self {
+            start: (location.row, start),
+            end: (location.row, location.column),
+        }
+    }
+
+    pub fn contains(&self, row: usize, column: usize) -> bool {
+        (self.start..=self.end).contains(&(row, column))
+    }
+
+    fn extend(&mut self, other: Span) {
+        self.start = self.start.min(other.start);
+        self.end = self.end.max(other.end);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Class,
+    Function,
+    Interface,
+    Method,
+    Parameter,
+    /// A `let`, `for` or `catch` variable.
+    Variable,
+}
+
+/// A declared name.
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub span: Span,
+    /// The index of the scope it is declared in, or `None` for the global
+    /// scope.
+    pub scope: Option<usize>,
+}
+
+/// A use of a declared name.
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub span: Span,
+    /// The index of the symbol it refers to.
+    pub symbol: usize,
+    /// Whether it is assigned to.
+    pub write: bool,
+}
+
+/// A local scope, like a function or block body.
+#[derive(Debug, Clone)]
+pub struct Scope {
+    /// From the first to the last name, keyword, operator or brace in the
+    /// scope. Only blocks and function bodies record their braces, so the
+    /// bodies of statements leave them out, along with literals. `None` if
+    /// there are none.
+    pub span: Option<Span>,
+    pub parent: Option<usize>,
+}
+
+/// The declarations, references and scopes the resolver found, for editors to
+/// go to definitions, find references and rename.
+#[derive(Debug, Default)]
+pub struct SymbolIndex {
+    symbols: Vec<Symbol>,
+    references: Vec<Reference>,
+    scopes: Vec<Scope>,
+}
+
+impl SymbolIndex {
+    /// Every declaration, in the order it was resolved.
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    pub fn references(&self) -> &[Reference] {
+        &self.references
+    }
+
+    pub fn scopes(&self) -> &[Scope] {
+        &self.scopes
+    }
+
+    /// The index of the symbol declared or referred to at a 1-based row and
+    /// column.
+    pub fn symbol_at(&self, row: usize, column: usize) -> Option<usize> {
+        let declared = self.symbols.iter().rposition(|symbol| symbol.span.contains(row, column));
+        declared.or_else(|| {
+            self.references
+                .iter()
+                .rfind(|reference| reference.span.contains(row, column))
+                .map(|reference| reference.symbol)
+        })
+    }
+
+    /// The declaration of the name at a 1-based row and column.
+    pub fn definition_at(&self, row: usize, column: usize) -> Option<&Symbol> {
+        self.symbol_at(row, column).map(|symbol| &self.symbols[symbol])
+    }
+
+    /// The references to a symbol, in the order they were resolved.
+    pub fn references_to(&self, symbol: usize) -> impl Iterator<Item = &Reference> {
+        self.references.iter().filter(move |reference| reference.symbol == symbol)
+    }
+
+    /// Where a symbol is declared and every reference to it in source order,
+    /// which is what renaming it changes.
+    pub fn occurrences(&self, symbol: usize) -> Vec<Span> {
+        let declaration = self.symbols.get(symbol).map(|symbol| symbol.span);
+        let mut spans: Vec<Span> = declaration
+            .into_iter()
+            .chain(self.references_to(symbol).map(|reference| reference.span))
+            .collect();
+        spans.sort_by_key(|span| span.start);
+        spans
+    }
+
+    /// The index of the innermost scope at a 1-based row and column, or
+    /// `None` in the global scope.
+    pub fn scope_at(&self, row: usize, column: usize) -> Option<usize> {
+        // Scopes are numbered before the scopes nested in them
+        self.scopes
+            .iter()
+            .rposition(|scope| scope.span.is_some_and(|span| span.contains(row, column)))
+    }
+
+    pub(crate) fn declare(&mut self, name: &Token, kind: SymbolKind, scope: Option<usize>) -> usize {
+        self.symbols.push(Symbol {
+            name: name.lexeme().to_string(),
+            kind,
+            span: Span::of(name),
+            scope,
+        });
+        self.symbols.len() - 1
+    }
+
+    pub(crate) fn refer(&mut self, span: Span, symbol: usize, write: bool) {
+        self.references.push(Reference { span, symbol, write });
+    }
+
+    pub(crate) fn begin_scope(&mut self, parent: Option<usize>) -> usize {
+        self.scopes.push(Scope { span: None, parent });
+        self.scopes.len() - 1
+    }
+
+    /// Widens a scope to cover a span, and the scopes around it with it.
+    pub(crate) fn extend_scope(&mut self, scope: usize, span: Span) {
+        let mut scope = Some(scope);
+        while let Some(index) = scope {
+            let scope_span = self.scopes[index].span.get_or_insert(span);
+            scope_span.extend(span);
+            scope = self.scopes[index].parent;
+        }
+    }
+}
@@ -0,0 +1,93 @@
+/*
+ * Copyright (C) 2022 Umut İnan Erdoğan <umutinanerdogan@pm.me>
+ *
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at https://mozilla.org/MPL/2.0/.
+ */
+
+use interpreter::Interpreter;
+use lexer::Lexer;
+use parser::Parser;
+use resolver::Resolver;
+use resolver::symbols::{Span, SymbolKind};
+
+fn resolve(source: &str) -> Resolver {
+    let tokens = Lexer::new(source.to_string(), "<test>".to_string()).scan_tokens().to_vec();
+    let statements = Parser::new(tokens).parse().unwrap();
+
+    let mut resolver = Resolver::new();
+    resolver.resolve(&statements, &mut Interpreter::new()).unwrap();
+    resolver
+}
+
+fn span(row: usize, start: usize, end: usize) -> Span {
+    Span { start: (row, start), end: (row, end) }
+}
+
+#[test]
+fn references_link_to_declarations() {
+    let resolver = resolve(r#"fn area(width, height) {
+    let size = width * height;
+    size = size + 0;
+    size
+}
+fn show() { print(area(2, 3), later); }
+let later = 1;"#);
+    let symbols = resolver.symbols();
+
+    let size = symbols.definition_at(4, 6).unwrap();
+    assert_eq!((size.name.as_str(), size.kind, size.span), ("size", SymbolKind::Variable, span(2, 9, 12)));
+
+    let size = symbols.symbol_at(2, 9).unwrap();
+    assert_eq!(symbols.occurrences(size), [span(2, 9, 12), span(3, 5, 8), span(3, 12, 15), span(4, 5, 8)]);
+    let writes: Vec<_> = symbols.references_to(size).map(|reference| reference.write).collect();
+    // The value of an assignment is resolved before its target
+    assert_eq!(writes, [false, true, false]);
+
+    assert_eq!(symbols.definition_at(2, 16).unwrap().kind, SymbolKind::Parameter);
+    assert_eq!(symbols.definition_at(6, 19).unwrap().kind, SymbolKind::Function);
+    assert_eq!(symbols.definition_at(6, 31).unwrap().span, span(7, 5, 9));
+
+    // Native functions aren't declared anywhere
+    assert!(symbols.symbol_at(6, 13).is_none());
+}
+
+#[test]
+fn scopes_cover_their_code() {
+    let resolver = resolve(r#"class Point {
+    fn init(x) { this.x = x; }
+}
+{
+    let p = Point(1);
+    print(p.x);
+};"#);
+    let symbols = resolver.symbols();
+
+    let init = symbols.definition_at(2, 9).unwrap();
+    assert_eq!(init.kind, SymbolKind::Method);
+
+    let class = init.scope.unwrap();
+    let method = symbols.scope_at(2, 24).unwrap();
+    assert_eq!(symbols.scopes()[method].parent, Some(class));
+    assert_eq!(symbols.scopes()[method].span, Some(Span { start: (2, 8), end: (2, 30) }));
+
+    let block = symbols.scope_at(5, 9).unwrap();
+    assert_eq!(symbols.definition_at(6, 11).unwrap().scope, Some(block));
+    assert_eq!(symbols.scopes()[block].parent, None);
+    assert_eq!(symbols.scopes()[block].span, Some(Span { start: (4, 1), end: (7, 1) }));
+    assert!(symbols.scope_at(7, 2).is_none());
+}
+
+#[test]
+fn bodies_without_names_span_their_braces() {
+    let resolver = resolve("fn one() { 1 }
+let two = { 2 };");
+    let symbols = resolver.symbols();
+
+    let body = symbols.scope_at(1, 12).unwrap();
+    assert_eq!(symbols.scopes()[body].span, Some(Span { start: (1, 4), end: (1, 14) }));
+
+    let block = symbols.scope_at(2, 13).unwrap();
+    assert_eq!(symbols.scopes()[block].span, Some(Span { start: (2, 11), end: (2, 15) }));
+}